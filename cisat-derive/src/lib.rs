@@ -0,0 +1,108 @@
+#![warn(clippy::all)]
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+//! This crate contains derive macros for the cisat crate. They are re-exported by cisat, so there
+//! is no need to depend on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Index, Member};
+
+/// This derives `PartialEq`, `Eq`, `PartialOrd` and `Ord` for a solution from the field marked
+/// `#[quality]`, which must be an `f64`. Solutions are ordered by quality, with non-finite
+/// qualities (NaN or infinite) treated as worse than any finite quality, and equal to each other.
+/// ```
+/// use cisat_derive::CisatSolution;
+///
+/// #[derive(CisatSolution)]
+/// struct Design {
+///     x: f64,
+///     #[quality]
+///     quality_scalar: f64,
+/// }
+///
+/// let a = Design { x: 1.0, quality_scalar: 2.0 };
+/// let b = Design { x: 0.0, quality_scalar: f64::NAN };
+/// assert!(a > b);
+/// assert!(a > Design { x: 0.0, quality_scalar: f64::INFINITY });
+/// ```
+#[proc_macro_derive(CisatSolution, attributes(quality))]
+pub fn derive_cisat_solution(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// This generates the comparison impls for a solution
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let quality = find_quality_field(input)?;
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::cmp::PartialEq for #name #type_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                ::std::cmp::Ord::cmp(self, other) == ::std::cmp::Ordering::Equal
+            }
+        }
+
+        impl #impl_generics ::std::cmp::Eq for #name #type_generics #where_clause {}
+
+        impl #impl_generics ::std::cmp::PartialOrd for #name #type_generics #where_clause {
+            fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
+                ::std::option::Option::Some(::std::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl #impl_generics ::std::cmp::Ord for #name #type_generics #where_clause {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                let (a, b): (f64, f64) = (self.#quality, other.#quality);
+                match (a.is_finite(), b.is_finite()) {
+                    (false, false) => ::std::cmp::Ordering::Equal,
+                    (false, true) => ::std::cmp::Ordering::Less,
+                    (true, false) => ::std::cmp::Ordering::Greater,
+                    (true, true) => a.partial_cmp(&b).unwrap(),
+                }
+            }
+        }
+    })
+}
+
+/// This finds the single field marked `#[quality]`
+fn find_quality_field(input: &DeriveInput) -> Result<Member, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "CisatSolution can only be derived for structs",
+            ))
+        }
+    };
+    let mut marked = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.attrs.iter().any(|attr| attr.path.is_ident("quality")))
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        });
+    let quality = marked.next().ok_or_else(|| {
+        let span = match fields {
+            Fields::Unit => input.span(),
+            _ => fields.span(),
+        };
+        Error::new(span, "CisatSolution needs one field marked #[quality]")
+    })?;
+    if marked.next().is_some() {
+        return Err(Error::new(
+            fields.span(),
+            "CisatSolution needs only one field marked #[quality]",
+        ));
+    }
+    Ok(quality)
+}
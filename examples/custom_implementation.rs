@@ -1,10 +1,11 @@
-use cisat::{AgentMethods, Cohort, Parameters, Solution, TeamMethods};
-use std::cmp::{Eq, Ord, Ordering, PartialOrd};
-use std::ops::Sub;
+use cisat::{AgentMethods, CisatSolution, Cohort, Parameters, Solution, TeamMethods};
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CisatSolution)]
 struct CustomProblem {
     x: usize,
+    #[quality]
+    quality_scalar: f64,
 }
 
 impl Solution for CustomProblem {
@@ -24,34 +25,6 @@ impl Solution for CustomProblem {
     }
 }
 
-impl Sub for CustomProblem {
-    type Output = f64;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        unimplemented!()
-    }
-}
-
-impl PartialEq for CustomProblem {
-    fn eq(&self, other: &Self) -> bool {
-        unimplemented!()
-    }
-}
-
-impl PartialOrd for CustomProblem {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        unimplemented!()
-    }
-}
-
-impl Eq for CustomProblem {}
-
-impl Ord for CustomProblem {
-    fn cmp(&self, other: &Self) -> Ordering {
-        unimplemented!()
-    }
-}
-
 struct CustomAgent {
     current_solution: CustomProblem,
     best_solution_so_far: CustomProblem,
@@ -66,15 +39,19 @@ impl AgentMethods<CustomProblem> for CustomAgent {
         unimplemented!()
     }
 
-    fn get_best_solution_so_far(&mut self) -> CustomProblem {
+    fn get_best_solution_so_far(&self) -> &CustomProblem {
+        unimplemented!()
+    }
+
+    fn get_current_solution(&self) -> &CustomProblem {
         unimplemented!()
     }
 
-    fn get_current_solution(&mut self) -> CustomProblem {
+    fn communicate(&mut self, solutions: &[Arc<CustomProblem>]) {
         unimplemented!()
     }
 
-    fn communicate(&mut self, solutions: Vec<CustomProblem>) {
+    fn set_current_solution(&mut self, _solution: CustomProblem) {
         unimplemented!()
     }
 }
@@ -98,7 +75,7 @@ impl TeamMethods<CustomProblem, CustomAgent> for CustomTeam {
         unimplemented!()
     }
 
-    fn get_best_solution_so_far(&mut self) -> CustomProblem {
+    fn get_best_solution_so_far(&self) -> &CustomProblem {
         unimplemented!()
     }
 }
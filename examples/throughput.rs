@@ -0,0 +1,76 @@
+use cisat::{problems::Ackley, CisatSolution, Cohort, CommunicationStyle, Parameters, Solution};
+use std::time::Instant;
+
+/// A design whose copies cost far more than its moves, like a large truss
+#[derive(Clone, Debug, CisatSolution)]
+struct LargeDesign {
+    x: Vec<f64>,
+    moves: usize,
+    #[quality]
+    quality_scalar: f64,
+}
+
+impl Solution for LargeDesign {
+    const NUMBER_OF_MOVE_OPERATORS: usize = 1;
+    const NUMBER_OF_OBJECTIVES: usize = 1;
+
+    fn new() -> Self {
+        LargeDesign {
+            x: vec![1.0; 20_000],
+            moves: 0,
+            quality_scalar: -20_000.0,
+        }
+    }
+
+    fn apply_move_operator(&mut self, _move_index: usize, temperature: f64) {
+        // Nudge one coordinate toward zero, and update the sum of squares to match
+        let i = self.moves % self.x.len();
+        let old = self.x[i];
+        self.x[i] -= 0.1 * temperature * old;
+        self.quality_scalar += old * old - self.x[i] * self.x[i];
+        self.moves += 1;
+    }
+
+    fn get_quality_scalar(&self) -> f64 {
+        self.quality_scalar
+    }
+}
+
+/// Runs one team of ten agents that meets every iteration, and gives the median number of agent
+/// iterations per second over a few repeats
+fn agent_iterations_per_second<S: Solution>() -> f64 {
+    let parameters = Parameters {
+        number_of_teams: 1,
+        number_of_agents: 10,
+        number_of_iterations: 2_000,
+        communication: CommunicationStyle::RegularInterval { interval: 1 },
+        seed: Some(0),
+        ..Default::default()
+    };
+    let mut rates: Vec<f64> = (0..5)
+        .map(|_| {
+            let mut x = Cohort::<S>::new(parameters.clone());
+            let started = Instant::now();
+            x.solve();
+            (parameters.number_of_agents * parameters.number_of_iterations) as f64
+                / started.elapsed().as_secs_f64()
+        })
+        .collect();
+    rates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    rates[rates.len() / 2]
+}
+
+fn main() {
+    println!(
+        "Ackley<200>:  {:.0} agent iterations per second",
+        agent_iterations_per_second::<Ackley<200>>()
+    );
+    println!(
+        "Ackley<2000>: {:.0} agent iterations per second",
+        agent_iterations_per_second::<Ackley<2000>>()
+    );
+    println!(
+        "LargeDesign:  {:.0} agent iterations per second",
+        agent_iterations_per_second::<LargeDesign>()
+    );
+}
@@ -0,0 +1,189 @@
+//! This module contains the rules agents use to decide which solution to adopt when they
+//! communicate
+
+use super::super::utilities::{
+    normalization::normalize_qualities,
+    parameters::{AdoptionStyle, Parameters},
+    solution::compare_qualities,
+};
+use rand::{
+    distributions::{Distribution, Uniform, WeightedIndex},
+    RngCore,
+};
+use std::{cmp::Ordering, fmt::Debug};
+
+/// This trait is implemented by rules for choosing which solution an agent adopts when it
+/// communicates with its team. Built-in rules are selected through `AdoptionStyle`, and new rules
+/// can be plugged in with `AdoptionStyle::Custom`:
+/// ```
+/// use cisat::{AdoptionStrategy, AdoptionStyle, Cohort, CommunicationStyle, Parameters, problems::Ackley};
+/// use rand::RngCore;
+/// use std::sync::Arc;
+///
+/// /// Always adopt the solution of the next agent on the team
+/// #[derive(Debug)]
+/// struct PassItOn;
+///
+/// impl AdoptionStrategy for PassItOn {
+///     fn choose(&self, qualities: &[f64], own: usize, _: &Parameters, _: &mut dyn RngCore) -> usize {
+///         (own + 1) % qualities.len()
+///     }
+/// }
+///
+/// let parameters = Parameters {
+///     communication: CommunicationStyle::RegularInterval { interval: 10 },
+///     adoption: AdoptionStyle::Custom(Arc::new(PassItOn)),
+///     ..Parameters::default()
+/// };
+/// let mut x = Cohort::<Ackley<5>>::new(parameters);
+/// x.solve();
+/// ```
+pub trait AdoptionStrategy: Debug + Send + Sync {
+    /// Chooses the index of the solution to adopt, given the quality of every solution on the team
+    /// indexed by agent id, and the index `own` of the agent's own solution. Returning `own` keeps
+    /// the agent's current solution. All random draws should come from `rng`, so that seeded runs
+    /// are repeatable.
+    fn choose(
+        &self,
+        qualities: &[f64],
+        own: usize,
+        parameters: &Parameters,
+        rng: &mut dyn RngCore,
+    ) -> usize;
+}
+
+impl AdoptionStrategy for AdoptionStyle {
+    fn choose(
+        &self,
+        qualities: &[f64],
+        own: usize,
+        parameters: &Parameters,
+        rng: &mut dyn RngCore,
+    ) -> usize {
+        match self {
+            AdoptionStyle::WeightedDraw => weighted_draw(qualities, own, parameters, rng),
+            AdoptionStyle::GreedyBest => best_of(own, 0..qualities.len(), qualities),
+            // An empty tournament has no winner, so the agent keeps its own solution
+            AdoptionStyle::Tournament { size: 0 } => own,
+            AdoptionStyle::Tournament { size } => {
+                let uniform = Uniform::new(0, qualities.len());
+                let entrants: Vec<usize> = (0..*size).map(|_| uniform.sample(rng)).collect();
+                best_of(entrants[0], entrants.into_iter(), qualities)
+            }
+            // Without a positive temperature, only the best solution has any weight
+            AdoptionStyle::Boltzmann { temperature }
+                if *temperature <= 0.0 || temperature.is_nan() =>
+            {
+                best_of(own, 0..qualities.len(), qualities)
+            }
+            AdoptionStyle::Boltzmann { temperature } => {
+                let max = qualities
+                    .iter()
+                    .copied()
+                    .filter(|q| q.is_finite())
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let weights: Vec<f64> = qualities
+                    .iter()
+                    .map(|q| {
+                        if q.is_finite() {
+                            ((q - max) / temperature).exp()
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect();
+                draw_or_keep(weights, own, rng)
+            }
+            AdoptionStyle::OnlyIfBetter => {
+                let idx = weighted_draw(qualities, own, parameters, rng);
+                if compare_qualities(qualities[idx], qualities[own]) == Ordering::Greater {
+                    idx
+                } else {
+                    own
+                }
+            }
+            AdoptionStyle::RandomPeer => {
+                if qualities.len() < 2 {
+                    own
+                } else {
+                    // Draw from everyone else by skipping over our own index
+                    let idx = Uniform::new(0, qualities.len() - 1).sample(rng);
+                    if idx >= own {
+                        idx + 1
+                    } else {
+                        idx
+                    }
+                }
+            }
+            AdoptionStyle::Custom(strategy) => strategy.choose(qualities, own, parameters, rng),
+        }
+    }
+}
+
+/// This draws a solution with probability proportional to its normalized quality plus the quality
+/// bias, with the self bias added to the agent's own solution, as described by
+/// `QualityNormalization`
+fn weighted_draw(
+    qualities: &[f64],
+    own: usize,
+    parameters: &Parameters,
+    rng: &mut dyn RngCore,
+) -> usize {
+    let mut weights = normalize_qualities(qualities, &parameters.quality_normalization);
+    weights[own] += parameters.self_bias;
+    for (weight, quality) in weights.iter_mut().zip(qualities.iter()) {
+        *weight = if quality.is_finite() {
+            (*weight + parameters.quality_bias).max(0.0)
+        } else {
+            0.0
+        };
+    }
+    draw_or_keep(weights, own, rng)
+}
+
+/// This draws an index with probability proportional to its weight, or returns `own` if no index
+/// has any weight
+fn draw_or_keep(weights: Vec<f64>, own: usize, rng: &mut dyn RngCore) -> usize {
+    if weights.iter().all(|w| *w == 0.0) {
+        own
+    } else {
+        WeightedIndex::new(weights).unwrap().sample(rng)
+    }
+}
+
+/// This finds the index with the best quality among some candidates, keeping `first` unless
+/// another candidate is strictly better
+fn best_of(first: usize, candidates: impl Iterator<Item = usize>, qualities: &[f64]) -> usize {
+    candidates.fold(first, |best, idx| {
+        if compare_qualities(qualities[idx], qualities[best]) == Ordering::Greater {
+            idx
+        } else {
+            best
+        }
+    })
+}
+
+#[cfg(test)]
+mod adoption_tests {
+    use crate::{utilities::randomness::seeded_rng, AdoptionStrategy, AdoptionStyle, Parameters};
+
+    #[test]
+    fn test_built_in_strategies() {
+        let qualities = [-2.0, 5.0, f64::NAN, 1.0];
+        let parameters = Parameters::default();
+        let mut rng = seeded_rng(Some(0));
+        let mut choose =
+            |style: AdoptionStyle, own: usize| style.choose(&qualities, own, &parameters, &mut rng);
+        for _ in 0..100 {
+            assert_eq!(choose(AdoptionStyle::GreedyBest, 3), 1);
+            assert_eq!(choose(AdoptionStyle::Tournament { size: 50 }, 0), 1);
+            assert_ne!(choose(AdoptionStyle::Boltzmann { temperature: 1.0 }, 0), 2);
+            assert!([1, 3].contains(&choose(AdoptionStyle::OnlyIfBetter, 3)));
+            assert_eq!(choose(AdoptionStyle::OnlyIfBetter, 1), 1);
+            assert_ne!(choose(AdoptionStyle::RandomPeer, 3), 3);
+            assert_eq!(choose(AdoptionStyle::Tournament { size: 0 }, 3), 3);
+            assert_eq!(choose(AdoptionStyle::Boltzmann { temperature: 0.0 }, 0), 1);
+            assert_eq!(choose(AdoptionStyle::Boltzmann { temperature: -1.0 }, 0), 1);
+        }
+    }
+}
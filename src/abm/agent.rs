@@ -3,10 +3,18 @@
 //! ```
 
 use super::super::utilities::{
-    parameters::{OperationalLearning, Parameters, TemperatureSchedule},
-    Solution,
+    constraints::ConstraintHandler,
+    parameters::{
+        CandidateSelection, MeetingCost, OperationalLearning, Parameters, StagnationResponse,
+        StepSizeAdaptation, TemperatureSchedule,
+    },
+    Counters, LearnedMatrix, Solution,
 };
-use crate::utilities::randomness::{multinomial_draw, random_unit_draw};
+use super::{adoption::AdoptionStrategy, snapshot::AgentSnapshot};
+use crate::utilities::randomness::{lend_rng, multinomial_draw, seeded_rng};
+use log::warn;
+use rand::{rngs::StdRng, Rng};
+use std::{cmp::Ordering, mem::discriminant, sync::Arc};
 
 /// This is an agent, the fundamental building block for a CISAT team
 #[derive(Clone, Debug)]
@@ -16,48 +24,139 @@ pub struct Agent<S: Solution> {
     /// The iteration number as tracked by the agent
     iteration_number: u64,
     /// The lst operation performed by the agent
-    last_operation: u64,
+    last_operation: usize,
+    /// The operation performed before the last one
+    previous_operation: usize,
+    /// The learned probabilities of choosing each move operator, as a single row for multinomial
+    /// learning or as a row per previous operator for Markov learning
+    learning_matrix: Vec<Vec<f64>>,
     /// The temperature used by the agent
     temperature: f64,
+    /// How far along the temperature schedule the agent is, which is set back by reheating
+    annealing_step: f64,
+    /// The number of iterations since the agent last improved on its best solution
+    iterations_without_improvement: usize,
+    /// The step size handed to move operators
+    step_size: f64,
+    /// The number of successes counted toward the next step size adaptation
+    adaptation_successes: usize,
+    /// The number of iterations counted toward the next step size adaptation
+    adaptation_iterations: usize,
     /// The current solution quality scalar of the agent
     current_solution_quality: f64,
-    /// The current solution of the agent
-    current_solution: S,
+    /// The current solution of the agent, which is shared with the team when communicating
+    current_solution: Arc<S>,
     /// The best quality so far for the agent
     best_quality_so_far: f64,
     /// The best solution so far for the agent
-    best_solution_so_far: S,
+    best_solution_so_far: Arc<S>,
     /// The parameters container
     parameters: Parameters,
+    /// Counts of evaluations, move applications and communication
+    counters: Counters,
+    /// Compares solutions according to the constraint handling style
+    constraint_handler: ConstraintHandler,
+    /// The random number generator that all of the agent's draws come from
+    rng: StdRng,
 }
 
 /// This is a trait for implementing new agents
 pub trait AgentMethods<S: Solution>: Send {
     /// Generates a new agent
-    fn new(id: usize, parameters: Parameters) -> Self;
+    fn new(id: usize, parameters: Parameters) -> Self
+    where
+        Self: Sized;
     /// Iterates on the solution
     fn iterate(&mut self);
     /// Gets the best solution found by the agent so far
-    fn get_best_solution_so_far(&mut self) -> S;
+    fn get_best_solution_so_far(&self) -> &S;
     /// Gets the current solution of the agent
-    fn get_current_solution(&mut self) -> S;
-    /// Agent accepts rival solutions and interacts
-    fn communicate(&mut self, solutions: Vec<S>);
+    fn get_current_solution(&self) -> &S;
+    /// Gets a handle to the current solution that can be shared with the team. By default, this
+    /// copies the current solution.
+    fn get_shared_current_solution(&self) -> Arc<S> {
+        Arc::new(self.get_current_solution().clone())
+    }
+    /// Agent accepts rival solutions and interacts. The solutions are indexed by agent id, and are
+    /// shared by the whole team, so an agent only needs to copy one if it changes it.
+    fn communicate(&mut self, solutions: &[Arc<S>]);
+    /// Replaces the agent's current solution, such as when a `ReplicaTeam` swaps solutions between
+    /// agents
+    fn set_current_solution(&mut self, solution: S);
+    /// Gets the counts of evaluations, move applications and communication for the agent. By
+    /// default, nothing is counted.
+    fn get_counters(&self) -> Counters {
+        Counters::new(S::NUMBER_OF_MOVE_OPERATORS)
+    }
+    /// Whether the agent has stagnated and is waiting to restart from its team's best solution
+    fn is_stagnating(&self) -> bool {
+        false
+    }
+    /// Restarts the agent from a solution, such as its team's best
+    fn restart_from(&mut self, _solution: &S) {}
+    /// Changes the parameters the agent runs with from now on. The seed is only used to build the
+    /// agent, so changing it has no effect. By default, the agent keeps its old parameters.
+    fn set_parameters(&mut self, _parameters: Parameters) {}
+    /// Gets the move operator preferences the agent has learned, if it learns any
+    fn get_learned_matrix(&self) -> Option<LearnedMatrix> {
+        None
+    }
+    /// Gets a snapshot of the agent's state. By default, this has no temperature, step size or last
+    /// operation.
+    fn get_snapshot(&self) -> AgentSnapshot {
+        AgentSnapshot {
+            current_quality: self.get_current_solution().get_quality_scalar(),
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
+            temperature: None,
+            step_size: None,
+            last_operation: None,
+            reheats: self.get_counters().reheats,
+            restarts: self.get_counters().restarts,
+        }
+    }
 }
 
 impl<S: Solution> AgentMethods<S> for Agent<S> {
     fn new(id: usize, parameters: Parameters) -> Self {
-        let solution = S::new();
+        let mut rng = seeded_rng(parameters.seed);
+        let solution = {
+            let _rng = lend_rng(&mut rng);
+            S::new()
+        };
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        counters.count_new_solution();
+        if !solution.get_quality_scalar().is_finite() {
+            warn!(
+                "Agent {} started from a solution with non-finite quality {}",
+                id,
+                solution.get_quality_scalar()
+            );
+            counters.count_non_finite_quality();
+        }
+        let solution = Arc::new(solution);
         Agent {
             id,
             iteration_number: 1,
             last_operation: 0,
+            previous_operation: 0,
+            learning_matrix: initial_learning_matrix(
+                &parameters.operational_learning,
+                S::NUMBER_OF_MOVE_OPERATORS,
+            ),
             temperature: 0.0,
+            annealing_step: 1.0,
+            iterations_without_improvement: 0,
+            step_size: 1.0,
+            adaptation_successes: 0,
+            adaptation_iterations: 0,
             current_solution_quality: solution.get_quality_scalar(),
             best_quality_so_far: solution.get_quality_scalar(),
-            best_solution_so_far: solution.clone(),
-            current_solution: solution.clone(),
+            best_solution_so_far: Arc::clone(&solution),
+            current_solution: solution,
+            constraint_handler: ConstraintHandler::new(&parameters.constraint_handling),
             parameters,
+            counters,
+            rng,
         }
     }
 
@@ -68,74 +167,386 @@ impl<S: Solution> AgentMethods<S> for Agent<S> {
         // Generate a candidate
         let candidate = self.generate_candidate_solution();
 
-        // Compare candidate
-        if candidate > self.current_solution {
-            self.current_solution = candidate;
+        // Compare candidate, taking constraint violations into account. Candidates with non-finite
+        // quality are rejected, and any finite candidate replaces a non-finite current solution.
+        let candidate_quality = candidate.get_quality_scalar();
+        let mut improved = false;
+        let mut accepted = false;
+        if !candidate_quality.is_finite() {
+            warn!(
+                "Agent {} rejected a candidate with non-finite quality {}",
+                self.id, candidate_quality
+            );
+            self.counters.count_non_finite_quality();
         } else {
-            let delta = candidate.clone() - self.current_solution.clone();
-            let acceptance_probability = (delta / self.temperature).exp();
-            if random_unit_draw() < acceptance_probability {
-                self.current_solution = candidate;
+            let delta = self.constraint_handler.acceptance_delta(
+                &candidate,
+                &*self.current_solution,
+                &mut self.rng,
+            );
+            improved = delta > 0.0;
+            if !self.current_solution_quality.is_finite()
+                || delta > 0.0
+                || self.rng.gen::<f64>() < self.acceptance_probability(delta)
+            {
+                self.current_solution_quality = candidate_quality;
+                self.current_solution = Arc::new(candidate);
+                accepted = true;
             }
         }
+        self.constraint_handler.update(&*self.current_solution);
 
-        self.update_learning();
+        self.update_learning(improved);
+        self.adapt_step_size(improved, accepted);
 
         // Update best solution
-        if self.current_solution > self.best_solution_so_far {
-            self.best_solution_so_far = self.current_solution.clone();
-            self.best_quality_so_far = self.best_solution_so_far.get_quality_scalar();
+        if self
+            .constraint_handler
+            .compare_best(&*self.current_solution, &*self.best_solution_so_far)
+            == Ordering::Greater
+        {
+            self.best_solution_so_far = Arc::clone(&self.current_solution);
+            self.best_quality_so_far = self.current_solution_quality;
+            self.iterations_without_improvement = 0;
+        } else {
+            self.iterations_without_improvement += 1;
         }
 
         // Increment iteration number
         self.iteration_number += 1;
+        self.annealing_step += 1.0;
+
+        // Get out of a rut
+        self.respond_to_stagnation();
     }
 
-    fn get_best_solution_so_far(&mut self) -> S {
-        self.best_solution_so_far.clone()
+    fn get_best_solution_so_far(&self) -> &S {
+        &self.best_solution_so_far
     }
 
-    fn get_current_solution(&mut self) -> S {
-        self.current_solution.clone()
+    fn get_current_solution(&self) -> &S {
+        &self.current_solution
     }
 
-    fn communicate(&mut self, mut solutions: Vec<S>) {
-        // Get scalar vector
-        let mut qualities: Vec<f64> = solutions
-            .clone()
-            .into_iter()
-            .map(|x| x.get_quality_scalar() + self.parameters.quality_bias)
-            .collect();
+    fn get_shared_current_solution(&self) -> Arc<S> {
+        Arc::clone(&self.current_solution)
+    }
 
-        // Add in self-bias
-        qualities[self.id] += self.parameters.self_bias;
+    fn communicate(&mut self, solutions: &[Arc<S>]) {
+        // Pay for the meeting
+        if let MeetingCost::Evaluations { evaluations } = self.parameters.meeting_cost {
+            self.counters.count_meeting_evaluations(evaluations);
+        }
 
         // Choose solution
-        let idx = multinomial_draw(qualities);
+        let qualities: Vec<f64> = solutions.iter().map(|x| x.get_quality_scalar()).collect();
+        let idx =
+            self.parameters
+                .adoption
+                .choose(&qualities, self.id, &self.parameters, &mut self.rng);
+
+        // Merge the design into ours or copy it, unless it is already ours
+        if idx != self.id {
+            let child = if self.parameters.crossover_rate > 0.0
+                && self.rng.gen::<f64>() < self.parameters.crossover_rate
+            {
+                let _rng = lend_rng(&mut self.rng);
+                self.current_solution.crossover(&solutions[idx])
+            } else {
+                None
+            };
+            self.current_solution = match child {
+                Some(child) => {
+                    self.counters.count_crossover();
+                    if child.get_quality_scalar().is_finite() {
+                        Arc::new(child)
+                    } else {
+                        warn!(
+                            "Agent {} discarded a crossover with non-finite quality {}",
+                            self.id,
+                            child.get_quality_scalar()
+                        );
+                        self.counters.count_non_finite_quality();
+                        Arc::clone(&solutions[idx])
+                    }
+                }
+                None => Arc::clone(&solutions[idx]),
+            };
+            self.current_solution_quality = self.current_solution.get_quality_scalar();
+        }
+        self.counters.count_communication(idx != self.id);
+    }
+
+    fn get_counters(&self) -> Counters {
+        self.counters.clone()
+    }
+
+    fn is_stagnating(&self) -> bool {
+        matches!(
+            self.parameters.stagnation_response,
+            StagnationResponse::RestartFromTeamBest
+        ) && self.iterations_without_improvement >= self.parameters.stagnation_iterations
+    }
+
+    fn set_current_solution(&mut self, solution: S) {
+        self.current_solution_quality = solution.get_quality_scalar();
+        self.current_solution = Arc::new(solution);
+    }
+
+    fn restart_from(&mut self, solution: &S) {
+        self.set_current_solution(solution.clone());
+        self.iterations_without_improvement = 0;
+        self.counters.count_restart();
+    }
+
+    fn set_parameters(&mut self, parameters: Parameters) {
+        // What has been learned carries over, unless the agent starts learning a different way
+        if discriminant(&parameters.operational_learning)
+            != discriminant(&self.parameters.operational_learning)
+        {
+            self.learning_matrix = initial_learning_matrix(
+                &parameters.operational_learning,
+                S::NUMBER_OF_MOVE_OPERATORS,
+            );
+        }
+        if parameters.constraint_handling != self.parameters.constraint_handling {
+            self.constraint_handler = ConstraintHandler::new(&parameters.constraint_handling);
+        }
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    fn get_learned_matrix(&self) -> Option<LearnedMatrix> {
+        match self.parameters.operational_learning {
+            OperationalLearning::Multinomial { .. } | OperationalLearning::Markov { .. } => {
+                Some(LearnedMatrix::new(self.learning_matrix.clone()))
+            }
+            _ => None,
+        }
+    }
 
-        // Extract the design
-        self.current_solution = solutions.remove(idx);
+    fn get_snapshot(&self) -> AgentSnapshot {
+        AgentSnapshot {
+            current_quality: self.current_solution_quality,
+            best_quality: self.best_quality_so_far,
+            temperature: Some(self.temperature),
+            step_size: Some(self.step_size),
+            last_operation: if self.iteration_number > 1 {
+                Some(self.last_operation)
+            } else {
+                None
+            },
+            reheats: self.counters.reheats,
+            restarts: self.counters.restarts,
+        }
     }
 }
 
 impl<S: Solution> Agent<S> {
-    /// This generates a new candidate solution for the agent
+    /// This generates the agent's candidate solutions for this iteration, and returns the one that
+    /// goes on to the acceptance test
     fn generate_candidate_solution(&mut self) -> S {
-        let mut candidate = self.current_solution.clone();
-        candidate.apply_move_operator(0, 1.0);
-        candidate
+        let mut move_indices: Vec<usize> = vec![];
+        let mut candidates = vec![];
+        for _ in 0..self.parameters.number_of_candidates.max(1) {
+            // Avoid the operators already used since every operator was last tried, if asked to
+            let move_index = if self.parameters.mix_move_operators {
+                let number_of_operators = S::NUMBER_OF_MOVE_OPERATORS.max(1);
+                let round_start = move_indices.len() / number_of_operators * number_of_operators;
+                self.select_move_operator(&move_indices[round_start..])
+            } else {
+                self.select_move_operator(&[])
+            };
+            let mut candidate = S::clone(&self.current_solution);
+            let temperature = self.move_temperature();
+            {
+                let _rng = lend_rng(&mut self.rng);
+                candidate.apply_scaled_move_operator(move_index, temperature, self.step_size);
+            }
+            self.counters.count_move_application(move_index);
+            move_indices.push(move_index);
+            candidates.push(candidate);
+        }
+        let chosen = self.select_candidate(&candidates);
+        self.previous_operation = self.last_operation;
+        self.last_operation = move_indices[chosen];
+        candidates.swap_remove(chosen)
+    }
+
+    /// This selects the move operator to apply next, from the learned probabilities if the agent
+    /// learns. Agents that do not learn apply the first operator, or the first one that is not
+    /// excluded. Excluded operators are only chosen if nothing else can be.
+    fn select_move_operator(&mut self, excluded: &[usize]) -> usize {
+        let weights = match self.parameters.operational_learning {
+            OperationalLearning::Multinomial { .. } => self.learning_matrix[0].clone(),
+            OperationalLearning::Markov { .. } => self.learning_matrix[self.last_operation].clone(),
+            _ => {
+                return (0..S::NUMBER_OF_MOVE_OPERATORS)
+                    .find(|i| !excluded.contains(i))
+                    .unwrap_or(0)
+            }
+        };
+        let allowed: Vec<f64> = weights
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if excluded.contains(&i) { 0.0 } else { *w })
+            .collect();
+        if allowed.iter().any(|w| *w > 0.0) {
+            multinomial_draw(allowed, &mut self.rng)
+        } else {
+            multinomial_draw(weights, &mut self.rng)
+        }
+    }
+
+    /// This chooses which candidate goes on to the acceptance test, taking constraint violations
+    /// into account
+    fn select_candidate(&mut self, candidates: &[S]) -> usize {
+        if candidates.len() == 1 {
+            return 0;
+        }
+        let best = (0..candidates.len()).fold(0, |best, i| {
+            if self
+                .constraint_handler
+                .compare(&candidates[i], &candidates[best])
+                == Ordering::Greater
+            {
+                i
+            } else {
+                best
+            }
+        });
+        match self.parameters.candidate_selection {
+            CandidateSelection::Boltzmann if self.temperature > 0.0 => {
+                let qualities: Vec<f64> = candidates
+                    .iter()
+                    .map(|x| self.constraint_handler.penalized_quality(x))
+                    .collect();
+                let max = qualities
+                    .iter()
+                    .copied()
+                    .filter(|q| q.is_finite())
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let weights: Vec<f64> = qualities
+                    .iter()
+                    .map(|q| {
+                        if q.is_finite() {
+                            ((q - max) / self.temperature).exp()
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect();
+                if weights.iter().any(|w| *w > 0.0) {
+                    multinomial_draw(weights, &mut self.rng)
+                } else {
+                    best
+                }
+            }
+            _ => best,
+        }
     }
 
-    /// This updates the agent's learning
-    fn update_learning(&mut self) {
+    /// This gives the temperature handed to move operators, which is one if the agent has no
+    /// temperature schedule
+    fn move_temperature(&self) -> f64 {
+        match self.parameters.temperature_schedule {
+            TemperatureSchedule::None => 1.0,
+            _ => self.temperature,
+        }
+    }
+
+    /// This counts an iteration toward the next step size adaptation, and adapts the step size at
+    /// the end of each window
+    fn adapt_step_size(&mut self, improved: bool, accepted: bool) {
+        let (success, target, window, factor) = match self.parameters.step_size_adaptation {
+            StepSizeAdaptation::OneFifthRule { window, factor } => (improved, 0.2, window, factor),
+            StepSizeAdaptation::AcceptanceRate {
+                target,
+                window,
+                factor,
+            } => (accepted, target, window, factor),
+            _ => return,
+        };
+        self.adaptation_iterations += 1;
+        if success {
+            self.adaptation_successes += 1;
+        }
+        if self.adaptation_iterations >= window {
+            let rate = self.adaptation_successes as f64 / self.adaptation_iterations as f64;
+            if rate > target {
+                self.step_size *= factor;
+            } else if rate < target {
+                self.step_size /= factor;
+            }
+            self.adaptation_successes = 0;
+            self.adaptation_iterations = 0;
+        }
+    }
+
+    /// This gives the probability of accepting a move that changes the quality by `delta`. At zero
+    /// temperature, only moves that do not worsen the quality are accepted.
+    fn acceptance_probability(&self, delta: f64) -> f64 {
+        if delta >= 0.0 {
+            1.0
+        } else if self.temperature > 0.0 {
+            (delta / self.temperature).exp()
+        } else {
+            0.0
+        }
+    }
+
+    /// This updates the agent's learning, reinforcing the last operation if it improved on the
+    /// current solution. Multinomial learning updates its only row, and Markov learning updates the
+    /// row of the operation that came before.
+    fn update_learning(&mut self, improved: bool) {
         match self.parameters.operational_learning {
-            OperationalLearning::Multinomial { .. } => {}
-            OperationalLearning::Markov { .. } => {}
+            OperationalLearning::Multinomial { learning_rate, .. } => {
+                reinforce(
+                    &mut self.learning_matrix[0],
+                    self.last_operation,
+                    learning_rate,
+                    improved,
+                );
+            }
+            OperationalLearning::Markov { learning_rate, .. } => {
+                reinforce(
+                    &mut self.learning_matrix[self.previous_operation],
+                    self.last_operation,
+                    learning_rate,
+                    improved,
+                );
+            }
             OperationalLearning::HiddenMarkov { .. } => {}
             _ => {}
         }
     }
 
+    /// This reheats or restarts the agent once it has gone too long without improving. Restarting
+    /// from the team's best solution is left to the team.
+    fn respond_to_stagnation(&mut self) {
+        if self.iterations_without_improvement < self.parameters.stagnation_iterations {
+            return;
+        }
+        match self.parameters.stagnation_response {
+            StagnationResponse::Reheat { fraction } => {
+                self.annealing_step = 1.0 / fraction;
+                self.iterations_without_improvement = 0;
+                self.counters.count_reheat();
+            }
+            StagnationResponse::Restart => {
+                self.counters.count_new_solution();
+                let solution = {
+                    let _rng = lend_rng(&mut self.rng);
+                    S::new()
+                };
+                self.restart_from(&solution);
+            }
+            _ => {}
+        }
+    }
+
     /// This updates the agent's temperature
     fn update_temperature(&mut self) {
         match self.parameters.temperature_schedule {
@@ -144,22 +555,261 @@ impl<S: Solution> Agent<S> {
                 delta,
                 dwell,
             } => {
-                self.temperature = initial_temperature / (self.iteration_number as f64);
+                self.temperature = initial_temperature / self.annealing_step;
             }
             TemperatureSchedule::Cauchy {
                 initial_temperature,
                 delta,
                 dwell,
             } => {
-                self.temperature = initial_temperature / (self.iteration_number as f64);
+                self.temperature = initial_temperature / self.annealing_step;
             }
             TemperatureSchedule::Geometric {
                 initial_temperature,
                 dwell,
             } => {
-                self.temperature = initial_temperature / (self.iteration_number as f64);
+                self.temperature = initial_temperature / self.annealing_step;
+            }
+            TemperatureSchedule::Fixed { temperature } => {
+                self.temperature = temperature;
             }
             _ => {}
         }
     }
 }
+
+/// This builds the matrix an agent starts learning from, normalizing each row of the initial
+/// learning matrix, or starting from uniform probabilities if none was given
+fn initial_learning_matrix(
+    operational_learning: &OperationalLearning,
+    number_of_operators: usize,
+) -> Vec<Vec<f64>> {
+    let uniform = vec![1.0 / number_of_operators as f64; number_of_operators];
+    let normalize = |row: &Vec<f64>| -> Vec<f64> {
+        if row.len() != number_of_operators {
+            panic!(
+                "Each row of the initial learning matrix must have {} entries, one per move operator.",
+                number_of_operators
+            );
+        }
+        let total: f64 = row.iter().sum();
+        if total > 0.0 {
+            row.iter().map(|x| x / total).collect()
+        } else {
+            uniform.clone()
+        }
+    };
+    match operational_learning {
+        OperationalLearning::Multinomial {
+            initial_learning_matrix,
+            ..
+        } => {
+            if initial_learning_matrix.is_empty() {
+                vec![uniform.clone()]
+            } else {
+                vec![normalize(initial_learning_matrix)]
+            }
+        }
+        OperationalLearning::Markov {
+            initial_learning_matrix,
+            ..
+        } => {
+            if initial_learning_matrix.is_empty() {
+                vec![uniform.clone(); number_of_operators]
+            } else if initial_learning_matrix.len() != number_of_operators {
+                panic!(
+                    "The initial learning matrix must have {} rows, one per move operator.",
+                    number_of_operators
+                );
+            } else {
+                initial_learning_matrix.iter().map(normalize).collect()
+            }
+        }
+        _ => vec![],
+    }
+}
+
+/// This moves a row of probabilities toward the given operation if it was a success, or spreads
+/// them over the other operations if it was not, by a fraction given by the learning rate
+fn reinforce(probabilities: &mut [f64], operation: usize, learning_rate: f64, success: bool) {
+    let number_of_others = (probabilities.len().max(2) - 1) as f64;
+    for (i, probability) in probabilities.iter_mut().enumerate() {
+        let target = match (success, i == operation) {
+            (true, true) => 1.0,
+            (true, false) => 0.0,
+            (false, true) => 0.0,
+            (false, false) => 1.0 / number_of_others,
+        };
+        *probability += learning_rate * (target - *probability);
+    }
+}
+
+#[cfg(test)]
+mod agent_tests {
+    use crate::{
+        utilities::randomness::random_unit_draw, Agent, AgentMethods, CandidateSelection,
+        CisatSolution, CommunicationStyle, OperationalLearning, Parameters, Solution,
+        StepSizeAdaptation, Team, TeamMethods, TemperatureSchedule,
+    };
+
+    /// This is a problem whose evaluations sometimes fail with a NaN or infinite quality
+    #[derive(Clone, Debug, CisatSolution)]
+    struct Flaky {
+        /// The quality of the solution
+        #[quality]
+        quality_scalar: f64,
+    }
+
+    impl Solution for Flaky {
+        const NUMBER_OF_MOVE_OPERATORS: usize = 3;
+        const NUMBER_OF_OBJECTIVES: usize = 1;
+
+        fn new() -> Self {
+            Flaky {
+                quality_scalar: f64::NAN,
+            }
+        }
+
+        fn apply_move_operator(&mut self, move_index: usize, _temperature: f64) {
+            self.quality_scalar = match move_index {
+                0 => f64::NAN,
+                1 => f64::INFINITY,
+                _ => random_unit_draw(),
+            };
+        }
+
+        fn get_quality_scalar(&self) -> f64 {
+            self.quality_scalar
+        }
+    }
+
+    /// This is a problem whose quality is the temperature times the step size of the last move
+    #[derive(Clone, Debug, CisatSolution)]
+    struct Thermometer {
+        /// The quality of the solution
+        #[quality]
+        quality_scalar: f64,
+    }
+
+    impl Solution for Thermometer {
+        const NUMBER_OF_MOVE_OPERATORS: usize = 1;
+        const NUMBER_OF_OBJECTIVES: usize = 1;
+
+        fn new() -> Self {
+            Thermometer {
+                quality_scalar: 0.0,
+            }
+        }
+
+        fn apply_move_operator(&mut self, move_index: usize, temperature: f64) {
+            self.apply_scaled_move_operator(move_index, temperature, 1.0);
+        }
+
+        fn apply_scaled_move_operator(&mut self, _: usize, temperature: f64, step_size: f64) {
+            self.quality_scalar = temperature * step_size;
+        }
+
+        fn get_quality_scalar(&self) -> f64 {
+            self.quality_scalar
+        }
+    }
+
+    #[test]
+    fn test_moves_get_the_temperature_and_step_size() {
+        let parameters = Parameters {
+            temperature_schedule: TemperatureSchedule::Geometric {
+                initial_temperature: 10.0,
+                dwell: 1,
+            },
+            ..Parameters::default()
+                .with_seed(2)
+                .with_step_size_adaptation(StepSizeAdaptation::OneFifthRule {
+                    window: 1,
+                    factor: 2.0,
+                })
+        };
+        let mut agent = Agent::<Thermometer>::new(0, parameters);
+        agent.iterate();
+        assert_eq!(agent.get_current_solution().get_quality_scalar(), 10.0);
+
+        // The first move improved, so the step size doubled
+        assert_eq!(agent.get_snapshot().step_size, Some(2.0));
+    }
+
+    #[test]
+    fn test_non_finite_qualities_are_counted_and_never_best() {
+        // Learning agents draw every operator, including the one that gives finite qualities
+        let parameters = Parameters {
+            temperature_schedule: TemperatureSchedule::None,
+            communication: CommunicationStyle::RegularInterval { interval: 1 },
+            operational_learning: OperationalLearning::Multinomial {
+                learning_rate: 0.05,
+                initial_learning_matrix: vec![],
+            },
+            ..Parameters::default().with_seed(3)
+        };
+        let mut team = Team::<Flaky, Agent<Flaky>>::new(parameters);
+        team.solve();
+        assert!(team
+            .get_best_solution_so_far()
+            .get_quality_scalar()
+            .is_finite());
+        assert!(team.get_counters().non_finite_qualities > 3);
+    }
+
+    #[test]
+    fn test_learned_matrices_stay_probabilities() {
+        for operational_learning in &[
+            OperationalLearning::Multinomial {
+                learning_rate: 0.1,
+                initial_learning_matrix: vec![],
+            },
+            OperationalLearning::Markov {
+                learning_rate: 0.1,
+                initial_learning_matrix: vec![],
+            },
+        ] {
+            let parameters = Parameters {
+                operational_learning: operational_learning.clone(),
+                ..Parameters::default().with_seed(4)
+            };
+            let mut team = Team::<Flaky, Agent<Flaky>>::new(parameters);
+            team.solve();
+            assert_eq!(team.get_learned_matrices().len(), 3);
+            let average = team.get_average_learned_matrix().unwrap();
+            for row in average.rows.iter() {
+                assert_eq!(row.len(), 3);
+                assert!(row.iter().all(|x| *x >= 0.0));
+                assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_candidates_mix_move_operators() {
+        for candidate_selection in &[CandidateSelection::Best, CandidateSelection::Boltzmann] {
+            let parameters = Parameters {
+                mix_move_operators: true,
+                ..Parameters::default()
+                    .with_seed(6)
+                    .with_candidates(3)
+                    .with_candidate_selection(candidate_selection.clone())
+            };
+            let mut team = Team::<Flaky, Agent<Flaky>>::new(parameters);
+            team.solve();
+
+            // Every agent tries each of the three operators once per iteration
+            assert_eq!(team.get_counters().move_applications, vec![300; 3]);
+            assert!(team
+                .get_best_solution_so_far()
+                .get_quality_scalar()
+                .is_finite());
+        }
+
+        // Agents always generate at least one candidate, and without learning or mixing, they
+        // always apply the first operator
+        let mut team = Team::<Flaky, Agent<Flaky>>::new(Parameters::default().with_candidates(0));
+        team.solve();
+        assert_eq!(team.get_counters().move_applications, vec![300, 0, 0]);
+    }
+}
@@ -0,0 +1,603 @@
+//! This module contains baseline optimizers to compare CISAT teams against. They run on any
+//! `Solution` type within the same `Cohort` and `Team` machinery, and use one evaluation per agent
+//! per iteration, so their evaluation budgets match those of a team of the same size.
+
+use super::{
+    super::utilities::{
+        constraints::ConstraintHandler,
+        parameters::{MeetingCost, Parameters},
+        randomness::{lend_rng, seeded_rng},
+        Counters, LearnedMatrix, Solution,
+    },
+    agent::AgentMethods,
+    snapshot::{AgentSnapshot, TeamSnapshot},
+    team::{solve_within_budget, TeamMethods},
+};
+use crate::Agent;
+use log::warn;
+use rand::{rngs::StdRng, Rng};
+use std::{cmp::Ordering, marker::PhantomData, sync::Arc};
+
+/// This is a greedy hill-climbing agent. Each iteration, it applies a move operator drawn uniformly
+/// at random, and keeps the candidate only if it is no worse than the current solution. It works
+/// alone, so it sits through meetings without adopting anything.
+/// ```
+/// use cisat::{Cohort, HillClimber, Parameters, Team, problems::Ackley};
+/// type S = Ackley<5>;
+/// let mut x = Cohort::<S, HillClimber<S>, Team<S, HillClimber<S>>>::new(Parameters::default());
+/// x.solve();
+/// ```
+#[derive(Clone, Debug)]
+pub struct HillClimber<S: Solution> {
+    /// Agent id
+    id: usize,
+    /// The last operation performed by the agent, if any
+    last_operation: Option<usize>,
+    /// The current solution of the agent
+    current_solution: S,
+    /// The best solution so far for the agent
+    best_solution_so_far: S,
+    /// The parameters container
+    parameters: Parameters,
+    /// Counts of evaluations, move applications and communication
+    counters: Counters,
+    /// Compares solutions according to the constraint handling style
+    constraint_handler: ConstraintHandler,
+    /// The random number generator that all of the agent's draws come from
+    rng: StdRng,
+}
+
+impl<S: Solution> AgentMethods<S> for HillClimber<S> {
+    fn new(id: usize, parameters: Parameters) -> Self {
+        let mut rng = seeded_rng(parameters.seed);
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        let solution: S = new_solution(id, &mut counters, &mut rng);
+        HillClimber {
+            id,
+            last_operation: None,
+            current_solution: solution.clone(),
+            best_solution_so_far: solution,
+            constraint_handler: ConstraintHandler::new(&parameters.constraint_handling),
+            parameters,
+            counters,
+            rng,
+        }
+    }
+
+    fn iterate(&mut self) {
+        // Apply a random move operator
+        let move_index = self.rng.gen_range(0..S::NUMBER_OF_MOVE_OPERATORS);
+        let mut candidate = self.current_solution.clone();
+        {
+            let _rng = lend_rng(&mut self.rng);
+            candidate.apply_move_operator(move_index, 1.0);
+        }
+        self.counters.count_move_application(move_index);
+        self.last_operation = Some(move_index);
+
+        // Keep the candidate unless it is worse
+        if !candidate.get_quality_scalar().is_finite() {
+            warn!(
+                "Agent {} rejected a candidate with non-finite quality {}",
+                self.id,
+                candidate.get_quality_scalar()
+            );
+            self.counters.count_non_finite_quality();
+        } else if !self.current_solution.get_quality_scalar().is_finite()
+            || self.constraint_handler.acceptance_delta(
+                &candidate,
+                &self.current_solution,
+                &mut self.rng,
+            ) >= 0.0
+        {
+            self.current_solution = candidate;
+        }
+        self.constraint_handler.update(&self.current_solution);
+
+        // Update best solution
+        if self
+            .constraint_handler
+            .compare_best(&self.current_solution, &self.best_solution_so_far)
+            == Ordering::Greater
+        {
+            self.best_solution_so_far = self.current_solution.clone();
+        }
+    }
+
+    fn get_best_solution_so_far(&self) -> &S {
+        &self.best_solution_so_far
+    }
+
+    fn get_current_solution(&self) -> &S {
+        &self.current_solution
+    }
+
+    fn communicate(&mut self, _solutions: &[Arc<S>]) {
+        sit_through_meeting(&self.parameters, &mut self.counters);
+    }
+
+    fn set_current_solution(&mut self, solution: S) {
+        self.current_solution = solution;
+    }
+
+    fn set_parameters(&mut self, parameters: Parameters) {
+        if parameters.constraint_handling != self.parameters.constraint_handling {
+            self.constraint_handler = ConstraintHandler::new(&parameters.constraint_handling);
+        }
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    fn get_counters(&self) -> Counters {
+        self.counters.clone()
+    }
+
+    fn get_snapshot(&self) -> AgentSnapshot {
+        AgentSnapshot {
+            current_quality: self.current_solution.get_quality_scalar(),
+            best_quality: self.best_solution_so_far.get_quality_scalar(),
+            temperature: None,
+            step_size: None,
+            last_operation: self.last_operation,
+            reheats: self.counters.reheats,
+            restarts: self.counters.restarts,
+        }
+    }
+}
+
+/// This is a random search agent. Each iteration, it generates a brand new solution and keeps the
+/// best one it has seen. It works alone, so it sits through meetings without adopting anything.
+/// ```
+/// use cisat::{Cohort, Parameters, RandomSearch, Team, problems::Ackley};
+/// type S = Ackley<5>;
+/// let mut x = Cohort::<S, RandomSearch<S>, Team<S, RandomSearch<S>>>::new(Parameters::default());
+/// x.solve();
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomSearch<S: Solution> {
+    /// Agent id
+    id: usize,
+    /// The solution generated most recently
+    current_solution: S,
+    /// The best solution so far for the agent
+    best_solution_so_far: S,
+    /// The parameters container
+    parameters: Parameters,
+    /// Counts of evaluations, move applications and communication
+    counters: Counters,
+    /// Compares solutions according to the constraint handling style
+    constraint_handler: ConstraintHandler,
+    /// The random number generator that all of the agent's draws come from
+    rng: StdRng,
+}
+
+impl<S: Solution> AgentMethods<S> for RandomSearch<S> {
+    fn new(id: usize, parameters: Parameters) -> Self {
+        let mut rng = seeded_rng(parameters.seed);
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        let solution: S = new_solution(id, &mut counters, &mut rng);
+        RandomSearch {
+            id,
+            current_solution: solution.clone(),
+            best_solution_so_far: solution,
+            constraint_handler: ConstraintHandler::new(&parameters.constraint_handling),
+            parameters,
+            counters,
+            rng,
+        }
+    }
+
+    fn iterate(&mut self) {
+        self.current_solution = new_solution(self.id, &mut self.counters, &mut self.rng);
+        if self
+            .constraint_handler
+            .compare_best(&self.current_solution, &self.best_solution_so_far)
+            == Ordering::Greater
+        {
+            self.best_solution_so_far = self.current_solution.clone();
+        }
+    }
+
+    fn get_best_solution_so_far(&self) -> &S {
+        &self.best_solution_so_far
+    }
+
+    fn get_current_solution(&self) -> &S {
+        &self.current_solution
+    }
+
+    fn communicate(&mut self, _solutions: &[Arc<S>]) {
+        sit_through_meeting(&self.parameters, &mut self.counters);
+    }
+
+    fn set_current_solution(&mut self, solution: S) {
+        self.current_solution = solution;
+    }
+
+    fn set_parameters(&mut self, parameters: Parameters) {
+        if parameters.constraint_handling != self.parameters.constraint_handling {
+            self.constraint_handler = ConstraintHandler::new(&parameters.constraint_handling);
+        }
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    fn get_counters(&self) -> Counters {
+        self.counters.clone()
+    }
+}
+
+/// This is a team with a single agent, which makes as many moves each iteration as a full team
+/// would make in total, so that it has the same evaluation budget. With the default `Agent`, this
+/// is single-agent simulated annealing.
+///
+/// Each move is a full iteration of the agent, so its temperature schedule and stagnation count
+/// advance `number_of_agents` times per team iteration. A schedule tuned for a team therefore cools
+/// that many times faster here, and its dwell or stagnation iterations may need scaling to match.
+/// ```
+/// use cisat::{Agent, Cohort, Parameters, SoloTeam, problems::Ackley};
+/// type S = Ackley<5>;
+/// let mut x = Cohort::<S, Agent<S>, SoloTeam<S, Agent<S>>>::new(Parameters::default());
+/// x.solve();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SoloTeam<S, A = Agent<S>>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// The parameters that the team runs with
+    parameters: Parameters,
+    /// iteration number counter
+    iteration_number: usize,
+    /// The only agent on the team
+    agent: A,
+    /// Bookkeeping the solution type
+    solution_type: PhantomData<S>,
+}
+
+impl<S, A> TeamMethods<S, A> for SoloTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// This generates a new team, seeding the agent from the team's random number generator
+    fn new(parameters: Parameters) -> Self {
+        let mut rng = seeded_rng(parameters.seed);
+        SoloTeam {
+            agent: A::new(
+                0,
+                Parameters {
+                    seed: parameters.seed.map(|_| rng.gen()),
+                    ..parameters.clone()
+                },
+            ),
+            parameters,
+            iteration_number: 1,
+            solution_type: Default::default(),
+        }
+    }
+
+    /// This runs a single iteration, in which the agent moves once for every agent on a full team
+    fn iterate(&mut self) {
+        for _ in 0..self.parameters.number_of_agents {
+            self.agent.iterate();
+        }
+        self.iteration_number += 1;
+    }
+
+    /// There is nobody to talk to
+    fn communicate(&mut self) {}
+
+    fn set_parameters(&mut self, parameters: Parameters) {
+        self.agent.set_parameters(parameters.clone());
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    /// This runs a bunch of iterations to solve, stopping early if the evaluation budget runs out
+    fn solve(&mut self) {
+        self.solve_with_callback(&mut |_| {});
+    }
+
+    /// This solves as `solve` does, calling `after_iteration` after every iteration
+    fn solve_with_callback(&mut self, after_iteration: &mut dyn FnMut(&Self)) {
+        let parameters = self.parameters.clone();
+        solve_within_budget::<S, A, _>(self, &parameters, after_iteration);
+    }
+
+    fn get_best_solution_so_far(&self) -> &S {
+        self.agent.get_best_solution_so_far()
+    }
+
+    fn get_counters(&self) -> Counters {
+        self.agent.get_counters()
+    }
+
+    fn get_learned_matrices(&self) -> Vec<LearnedMatrix> {
+        self.agent.get_learned_matrix().into_iter().collect()
+    }
+
+    fn get_snapshot(&self) -> TeamSnapshot {
+        TeamSnapshot {
+            iteration: self.iteration_number - 1,
+            agents: vec![self.agent.get_snapshot()],
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
+        }
+    }
+}
+
+impl<S, A> Default for SoloTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    fn default() -> Self {
+        SoloTeam::new(Default::default())
+    }
+}
+
+/// This is a simple evolutionary algorithm with a population of `Parameters::number_of_agents`
+/// solutions. Each iteration, it breeds as many offspring as there are members, each from a parent
+/// chosen by binary tournament. An offspring is a crossover with a second parent with probability
+/// `Parameters::crossover_rate` if the solution type supports it, and otherwise the parent with a
+/// random move operator applied as mutation. The best members of the parents and offspring
+/// together survive to the next iteration.
+/// ```
+/// use cisat::{Agent, Cohort, EvolutionaryTeam, Parameters, problems::Ackley};
+/// type S = Ackley<5>;
+/// let mut x = Cohort::<S, Agent<S>, EvolutionaryTeam<S>>::new(Parameters::default());
+/// x.solve();
+/// ```
+#[derive(Clone, Debug)]
+pub struct EvolutionaryTeam<S: Solution> {
+    /// The parameters that the team runs with
+    parameters: Parameters,
+    /// iteration number counter
+    iteration_number: usize,
+    /// The members of the population, best first
+    population: Vec<S>,
+    /// Counts of evaluations, move applications and crossovers
+    counters: Counters,
+    /// Compares solutions according to the constraint handling style
+    constraint_handler: ConstraintHandler,
+    /// The random number generator that all of the team's draws come from
+    rng: StdRng,
+}
+
+impl<S: Solution> EvolutionaryTeam<S> {
+    /// This picks the better of two members drawn at random
+    fn tournament(&mut self) -> usize {
+        let (a, b) = (
+            self.rng.gen_range(0..self.population.len()),
+            self.rng.gen_range(0..self.population.len()),
+        );
+        match self
+            .constraint_handler
+            .compare(&self.population[a], &self.population[b])
+        {
+            Ordering::Less => b,
+            _ => a,
+        }
+    }
+
+    /// This sorts the population from best to worst
+    fn rank(&mut self) {
+        let constraint_handler = &self.constraint_handler;
+        self.population
+            .sort_by(|a, b| constraint_handler.compare(b, a));
+    }
+}
+
+impl<S: Solution> TeamMethods<S> for EvolutionaryTeam<S> {
+    /// This generates a new population
+    fn new(parameters: Parameters) -> Self {
+        let mut rng = seeded_rng(parameters.seed);
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        let population = (0..parameters.number_of_agents)
+            .map(|i| new_solution(i, &mut counters, &mut rng))
+            .collect();
+        let mut team = EvolutionaryTeam {
+            constraint_handler: ConstraintHandler::new(&parameters.constraint_handling),
+            parameters,
+            iteration_number: 1,
+            population,
+            counters,
+            rng,
+        };
+        team.rank();
+        team
+    }
+
+    /// This runs a single generation
+    fn iterate(&mut self) {
+        // Breed offspring
+        let mut offspring = vec![];
+        for _ in 0..self.parameters.number_of_agents {
+            let parent = self.tournament();
+            let crossover = if self.rng.gen::<f64>() < self.parameters.crossover_rate {
+                let other = self.tournament();
+                let _rng = lend_rng(&mut self.rng);
+                self.population[parent].crossover(&self.population[other])
+            } else {
+                None
+            };
+            let child = match crossover {
+                Some(child) => {
+                    self.counters.count_crossover();
+                    child
+                }
+                None => {
+                    let move_index = self.rng.gen_range(0..S::NUMBER_OF_MOVE_OPERATORS);
+                    let mut child = self.population[parent].clone();
+                    {
+                        let _rng = lend_rng(&mut self.rng);
+                        child.apply_move_operator(move_index, 1.0);
+                    }
+                    self.counters.count_move_application(move_index);
+                    child
+                }
+            };
+            if child.get_quality_scalar().is_finite() {
+                offspring.push(child);
+            } else {
+                warn!(
+                    "An evolutionary team discarded an offspring with non-finite quality {}",
+                    child.get_quality_scalar()
+                );
+                self.counters.count_non_finite_quality();
+            }
+        }
+
+        // Keep the best of the parents and offspring
+        self.population.extend(offspring);
+        self.rank();
+        self.population.truncate(self.parameters.number_of_agents);
+        if let Some(best) = self.population.first() {
+            self.constraint_handler.update(best);
+        }
+
+        self.iteration_number += 1;
+    }
+
+    /// The population shares everything already
+    fn communicate(&mut self) {}
+
+    /// This changes the parameters from the next generation on. The size of the population stays
+    /// the same.
+    fn set_parameters(&mut self, parameters: Parameters) {
+        if parameters.constraint_handling != self.parameters.constraint_handling {
+            self.constraint_handler = ConstraintHandler::new(&parameters.constraint_handling);
+        }
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            number_of_agents: self.parameters.number_of_agents,
+            ..parameters
+        };
+    }
+
+    /// This runs a bunch of iterations to solve, stopping early if the evaluation budget runs out
+    fn solve(&mut self) {
+        self.solve_with_callback(&mut |_| {});
+    }
+
+    /// This solves as `solve` does, calling `after_iteration` after every iteration
+    fn solve_with_callback(&mut self, after_iteration: &mut dyn FnMut(&Self)) {
+        let parameters = self.parameters.clone();
+        solve_within_budget::<S, Agent<S>, _>(self, &parameters, after_iteration);
+    }
+
+    fn get_best_solution_so_far(&self) -> &S {
+        &self.population[0]
+    }
+
+    fn get_counters(&self) -> Counters {
+        self.counters.clone()
+    }
+
+    /// This takes a snapshot with one entry per member of the population
+    fn get_snapshot(&self) -> TeamSnapshot {
+        TeamSnapshot {
+            iteration: self.iteration_number - 1,
+            agents: self
+                .population
+                .iter()
+                .map(|x| AgentSnapshot {
+                    current_quality: x.get_quality_scalar(),
+                    best_quality: x.get_quality_scalar(),
+                    temperature: None,
+                    step_size: None,
+                    last_operation: None,
+                    reheats: 0,
+                    restarts: 0,
+                })
+                .collect(),
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
+        }
+    }
+}
+
+impl<S: Solution> Default for EvolutionaryTeam<S> {
+    fn default() -> Self {
+        EvolutionaryTeam::new(Default::default())
+    }
+}
+
+/// This generates and counts a new solution, drawing from `rng`
+fn new_solution<S: Solution>(id: usize, counters: &mut Counters, rng: &mut StdRng) -> S {
+    let solution = {
+        let _rng = lend_rng(rng);
+        S::new()
+    };
+    counters.count_new_solution();
+    if !solution.get_quality_scalar().is_finite() {
+        warn!(
+            "Agent {} generated a solution with non-finite quality {}",
+            id,
+            solution.get_quality_scalar()
+        );
+        counters.count_non_finite_quality();
+    }
+    solution
+}
+
+/// This charges a baseline agent for a meeting it takes nothing from
+fn sit_through_meeting(parameters: &Parameters, counters: &mut Counters) {
+    if let MeetingCost::Evaluations { evaluations } = parameters.meeting_cost {
+        counters.count_meeting_evaluations(evaluations);
+    }
+    counters.count_communication(false);
+}
+
+#[cfg(test)]
+mod baselines_tests {
+    use crate::{
+        problems::Ackley, Agent, AgentMethods, EvolutionaryTeam, HillClimber, Parameters,
+        RandomSearch, SoloTeam, Solution, Team, TeamMethods,
+    };
+
+    type S = Ackley<5>;
+
+    /// This solves a team and returns its counters and best quality
+    fn run<A: AgentMethods<S>, T: TeamMethods<S, A>>(parameters: Parameters) -> (usize, f64) {
+        let mut team = T::new(parameters);
+        team.solve();
+        (
+            team.get_counters().evaluations,
+            team.get_best_solution_so_far().get_quality_scalar(),
+        )
+    }
+
+    #[test]
+    fn test_baselines_match_budgets_and_repeat() {
+        let parameters = Parameters::default().with_seed(4);
+        let (team_evaluations, _) = run::<Agent<S>, Team<S, Agent<S>>>(parameters.clone());
+        let baselines = [
+            run::<Agent<S>, SoloTeam<S, Agent<S>>>,
+            run::<HillClimber<S>, Team<S, HillClimber<S>>>,
+            run::<RandomSearch<S>, Team<S, RandomSearch<S>>>,
+            run::<Agent<S>, EvolutionaryTeam<S>>,
+        ];
+        for baseline in baselines.iter() {
+            let (evaluations, quality) = baseline(parameters.clone());
+            assert!(team_evaluations.abs_diff(evaluations) < parameters.number_of_agents);
+            assert_eq!(quality, baseline(parameters.clone()).1);
+        }
+    }
+
+    #[test]
+    fn test_hill_climbers_never_get_worse() {
+        let mut team = Team::<S, HillClimber<S>>::new(Parameters::default().with_seed(2));
+        for _ in 0..50 {
+            for agent in team.step().agents {
+                assert_eq!(agent.current_quality, agent.best_quality);
+            }
+        }
+    }
+}
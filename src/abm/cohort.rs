@@ -1,14 +1,23 @@
 //! This module contains the Cohort class, a container for multiple Teams.
 
 use super::{
-    super::utilities::{parameters::Parameters, Solution},
+    super::utilities::{
+        constraints::ConstraintHandler, parameters::Parameters, Counters, LearnedMatrix, Solution,
+    },
     agent::{Agent, AgentMethods},
+    snapshot::CohortSnapshot,
     team::{Team, TeamMethods},
 };
 use crate::problems::Ackley;
+use crate::utilities::randomness::seeded_rng;
 use indicatif::ProgressBar;
-use rayon::prelude::*;
+use rand::Rng;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
 use std::marker::PhantomData;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
 
 /// This is the Cohort class, a container for multiple teams
 ///
@@ -51,6 +60,10 @@ where
     parameters: Parameters,
     /// This contains the teams in the cohort
     pub team_list: Vec<T>,
+    /// The number of iterations the cohort has been stepped through with `iterate` or `step`
+    iteration_number: usize,
+    /// The threads the teams run on, if the parameters ask for a particular number of them
+    thread_pool: Option<Arc<ThreadPool>>,
     /// Bookkeeping the solution type
     solution_type: PhantomData<S>,
     /// Bookkeeping the agent-type
@@ -65,10 +78,29 @@ where
 {
     /// This generates a new cohort
     pub fn new(parameters: Parameters) -> Cohort<S, A, T> {
+        // Catch invalid parameters before they cause trouble mid-run
+        parameters.verify();
+
+        // Seed each team from the cohort's random number generator
+        let mut rng = seeded_rng(parameters.seed);
         Cohort {
             team_list: (0..parameters.number_of_teams)
-                .map(|_| T::new(parameters.clone()))
+                .map(|_| {
+                    T::new(Parameters {
+                        seed: parameters.seed.map(|_| rng.gen()),
+                        ..parameters.clone()
+                    })
+                })
                 .collect(),
+            iteration_number: 0,
+            thread_pool: parameters.number_of_threads.map(|number_of_threads| {
+                Arc::new(
+                    ThreadPoolBuilder::new()
+                        .num_threads(number_of_threads)
+                        .build()
+                        .unwrap(),
+                )
+            }),
             solution_type: Default::default(),
             parameters,
             agent_type: Default::default(),
@@ -77,24 +109,245 @@ where
 
     /// This runs the cohort using parallelism
     pub fn solve(&mut self) {
-        self.team_list.par_iter_mut().for_each(|x| x.solve());
+        let team_list = &mut self.team_list;
+        run_on(&self.thread_pool, || {
+            team_list.par_iter_mut().for_each(|x| x.solve())
+        });
+    }
+
+    /// This runs the cohort using parallelism, calling `callback` with a `Progress` report after
+    /// every team iteration and whenever a team finishes. The callback may be called from several
+    /// threads at once. Teams are solved through `TeamMethods::solve_with_callback`, so teams that
+    /// only implement `solve` report their progress when they finish.
+    /// ```
+    /// use cisat::{Parameters, Cohort, problems::Ackley};
+    /// let mut x = Cohort::<Ackley<5>>::new(Parameters::default().with_teams(4));
+    /// x.solve_with_progress(|progress| {
+    ///     println!(
+    ///         "{}/{} teams, {}/{} iterations",
+    ///         progress.teams_completed,
+    ///         progress.number_of_teams,
+    ///         progress.iterations_completed,
+    ///         progress.total_iterations
+    ///     )
+    /// });
+    /// ```
+    pub fn solve_with_progress<F>(&mut self, callback: F)
+    where
+        F: Fn(&Progress) + Sync,
+    {
+        let number_of_teams = self.team_list.len();
+        let number_of_iterations = self.parameters.number_of_iterations;
+        let teams_completed = AtomicUsize::new(0);
+        let iterations_completed = AtomicUsize::new(0);
+        let best_quality_so_far = Mutex::new(f64::NEG_INFINITY);
+
+        // Builds a report from the shared state
+        let report = |best_quality: f64| Progress {
+            teams_completed: teams_completed.load(Ordering::SeqCst),
+            number_of_teams,
+            iterations_completed: iterations_completed.load(Ordering::SeqCst),
+            total_iterations: number_of_teams * number_of_iterations,
+            best_quality_so_far: best_quality,
+        };
+
+        let team_list = &mut self.team_list;
+        run_on(&self.thread_pool, || {
+            team_list.par_iter_mut().for_each(|team| {
+                let mut iterations = 0;
+                team.solve_with_callback(&mut |team| {
+                    // Count iterations up to the total, in case a custom team runs for longer
+                    if iterations < number_of_iterations {
+                        iterations += 1;
+                        iterations_completed.fetch_add(1, Ordering::SeqCst);
+                    }
+
+                    // Update the best quality and report
+                    let quality = team.get_best_solution_so_far().get_quality_scalar();
+                    let best_quality = {
+                        let mut best = best_quality_so_far.lock().unwrap();
+                        if quality > *best {
+                            *best = quality;
+                        }
+                        *best
+                    };
+                    callback(&report(best_quality));
+                });
+
+                // Count the remaining iterations as done if the team stopped early
+                iterations_completed.fetch_add(number_of_iterations - iterations, Ordering::SeqCst);
+                teams_completed.fetch_add(1, Ordering::SeqCst);
+                callback(&report(*best_quality_so_far.lock().unwrap()));
+            })
+        });
     }
 
-    /// This runs a single iteration
+    /// This runs a single iteration, skipping teams that have used up their evaluation budget.
+    /// Teams are iterated in parallel if `Parameters::parallel_teams` is set, which gives the same
+    /// results under a fixed seed.
     pub fn iterate(&mut self) {
-        self.team_list.iter_mut().for_each(|x| x.iterate());
+        let parameters = &self.parameters;
+        let team_list = &mut self.team_list;
+        if parameters.parallel_teams {
+            run_on(&self.thread_pool, || {
+                team_list
+                    .par_iter_mut()
+                    .filter(|x| !parameters.is_budget_exhausted(&x.get_counters()))
+                    .for_each(|x| x.iterate())
+            });
+        } else {
+            team_list
+                .iter_mut()
+                .filter(|x| !parameters.is_budget_exhausted(&x.get_counters()))
+                .for_each(|x| x.iterate());
+        }
+        self.iteration_number += 1;
+    }
+
+    /// This runs a single iteration and returns a snapshot of the cohort afterwards, or returns
+    /// `None` without iterating once the cohort has run for the number of iterations in its
+    /// parameters or every team has used up its evaluation budget. Between steps, the cohort can be
+    /// inspected freely.
+    /// ```
+    /// use cisat::{Parameters, Cohort, problems::Ackley};
+    /// let mut x = Cohort::<Ackley<5>>::new(Parameters::default());
+    /// while let Some(snapshot) = x.step() {
+    ///     if snapshot.best_quality > 20.0 {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub fn step(&mut self) -> Option<CohortSnapshot> {
+        let parameters = &self.parameters;
+        if self.iteration_number >= parameters.number_of_iterations
+            || self
+                .team_list
+                .iter()
+                .all(|x| parameters.is_budget_exhausted(&x.get_counters()))
+        {
+            return None;
+        }
+        self.iterate();
+        Some(self.get_snapshot())
+    }
+
+    /// This returns an iterator that steps through the rest of the run lazily, yielding a snapshot
+    /// after every iteration, as `step` does:
+    /// ```
+    /// use cisat::{Parameters, Cohort, problems::Ackley};
+    /// let mut x = Cohort::<Ackley<5>>::new(Parameters::default().with_iters(50));
+    /// let temperatures: Vec<Option<f64>> = x
+    ///     .steps()
+    ///     .map(|snapshot| snapshot.teams[0].agents[0].temperature)
+    ///     .collect();
+    /// assert_eq!(temperatures.len(), 50);
+    /// ```
+    pub fn steps(&mut self) -> impl Iterator<Item = CohortSnapshot> + '_ {
+        std::iter::from_fn(move || self.step())
+    }
+
+    /// Gets the parameters the cohort runs with
+    pub fn get_parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    /// Changes the parameters that every team and agent runs with from the next iteration on, such
+    /// as between steps. The seed, the numbers of teams and agents, and the number of threads are
+    /// only used to build the cohort, so changing them has no effect.
+    /// ```
+    /// use cisat::{CommunicationStyle, Cohort, Parameters, problems::Ackley};
+    /// let mut x = Cohort::<Ackley<5>>::new(Parameters::default());
+    /// for _ in 0..50 {
+    ///     x.step();
+    /// }
+    ///
+    /// // Let the agents start talking halfway through
+    /// x.set_parameters(Parameters {
+    ///     communication: CommunicationStyle::RegularInterval { interval: 5 },
+    ///     ..x.get_parameters().clone()
+    /// });
+    /// while x.step().is_some() {}
+    /// assert!(x.get_counters().communications > 0);
+    /// ```
+    pub fn set_parameters(&mut self, parameters: Parameters) {
+        parameters.verify();
+        self.team_list
+            .iter_mut()
+            .for_each(|x| x.set_parameters(parameters.clone()));
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            number_of_teams: self.parameters.number_of_teams,
+            number_of_agents: self.parameters.number_of_agents,
+            number_of_threads: self.parameters.number_of_threads,
+            ..parameters
+        };
+    }
+
+    /// Get a snapshot of every team in the cohort
+    pub fn get_snapshot(&self) -> CohortSnapshot {
+        CohortSnapshot {
+            iteration: self.iteration_number,
+            teams: self.team_list.iter().map(|x| x.get_snapshot()).collect(),
+            best_quality: self.get_best_solution_so_far(),
+        }
     }
 
     /// Get the current best solution
-    pub fn get_best_solution_so_far(&mut self) -> f64 {
-        (0..self.parameters.number_of_teams)
-            .map(|i| self.team_list[i].get_best_solution_so_far())
-            .collect::<Vec<S>>()
-            .into_iter()
-            .max()
+    pub fn get_best_solution_so_far(&self) -> f64 {
+        let constraint_handler = ConstraintHandler::new(&self.parameters.constraint_handling);
+        self.team_list
+            .iter()
+            .map(|x| x.get_best_solution_so_far())
+            .max_by(|a, b| constraint_handler.compare_best(*a, *b))
             .unwrap()
             .get_quality_scalar()
     }
+
+    /// Get the move operator preferences learned by each agent, team by team
+    pub fn get_learned_matrices(&self) -> Vec<Vec<LearnedMatrix>> {
+        self.team_list
+            .iter()
+            .map(|x| x.get_learned_matrices())
+            .collect()
+    }
+
+    /// Get the average of the move operator preferences learned by every agent in the cohort, if
+    /// any were learned
+    pub fn get_average_learned_matrix(&self) -> Option<LearnedMatrix> {
+        LearnedMatrix::average(&self.get_learned_matrices().concat()).ok()
+    }
+
+    /// Get the counters summed over all teams
+    pub fn get_counters(&self) -> Counters {
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        self.team_list
+            .iter()
+            .for_each(|x| counters += &x.get_counters());
+        counters
+    }
+}
+
+/// This runs `work` on the given thread pool, or on rayon's global pool if there is none
+fn run_on<R: Send>(thread_pool: &Option<Arc<ThreadPool>>, work: impl FnOnce() -> R + Send) -> R {
+    match thread_pool {
+        Some(thread_pool) => thread_pool.install(work),
+        None => work(),
+    }
+}
+
+/// This is a report on the progress of a cohort being solved with `Cohort::solve_with_progress`
+#[derive(Clone, Debug)]
+pub struct Progress {
+    /// The number of teams that have finished solving
+    pub teams_completed: usize,
+    /// The total number of teams in the cohort
+    pub number_of_teams: usize,
+    /// The number of iterations completed, summed over all teams
+    pub iterations_completed: usize,
+    /// The total number of iterations to run, summed over all teams
+    pub total_iterations: usize,
+    /// The best quality found by any team so far
+    pub best_quality_so_far: f64,
 }
 
 impl Default for Cohort {
@@ -102,3 +355,52 @@ impl Default for Cohort {
         Cohort::new(Default::default())
     }
 }
+
+#[cfg(test)]
+mod cohort_tests {
+    use crate::{problems::Ackley, Cohort, CommunicationStyle, Parameters};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_parallel_teams_are_deterministic() {
+        let parameters = Parameters {
+            communication: CommunicationStyle::ConstantFrequency { frequency: 0.2 },
+            ..Parameters::default().with_teams(6).with_seed(21)
+        };
+        let snapshots = |parameters: Parameters| -> Vec<_> {
+            Cohort::<Ackley<5>>::new(parameters).steps().collect()
+        };
+        assert_eq!(
+            snapshots(parameters.clone()),
+            snapshots(
+                parameters
+                    .with_parallel_teams(true)
+                    .with_number_of_threads(2)
+            )
+        );
+    }
+
+    #[test]
+    fn test_progress_matches_solve() {
+        let parameters = Parameters::default()
+            .with_teams(3)
+            .with_seed(4)
+            .with_evaluation_budget(90);
+        let mut plain = Cohort::<Ackley<5>>::new(parameters.clone());
+        plain.solve();
+        let mut reported = Cohort::<Ackley<5>>::new(parameters);
+        let last = Mutex::new(None);
+        reported.solve_with_progress(|progress| *last.lock().unwrap() = Some(progress.clone()));
+        let last = last.into_inner().unwrap().unwrap();
+        assert_eq!(last.teams_completed, 3);
+        assert_eq!(last.iterations_completed, last.total_iterations);
+        assert_eq!(
+            last.best_quality_so_far,
+            reported.get_best_solution_so_far()
+        );
+        assert_eq!(
+            plain.get_best_solution_so_far(),
+            reported.get_best_solution_so_far()
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! This module contains the EventTeam class, a set of Agents that work at their own pace on a
+//! shared simulated clock
+
+use super::{
+    super::utilities::{
+        constraints::ConstraintHandler,
+        parameters::{MeetingCost, Parameters, WorkPace},
+        randomness::{random_exponential, seeded_rng},
+        Counters, LearnedMatrix, Solution,
+    },
+    agent::AgentMethods,
+    snapshot::TeamSnapshot,
+    team::{restart_stagnating_agents, solve_within_budget, TeamMethods},
+};
+use crate::{Agent, CommunicationStyle};
+use rand::{rngs::StdRng, Rng};
+use std::{marker::PhantomData, sync::Arc};
+
+/// This is the EventTeam construct, which contains a set of Agents that each work at their own
+/// pace, as given by `Parameters::work_pace`. Rather than every agent making one move per
+/// iteration, each iteration advances a shared clock by one unit of time, and agents move whenever
+/// their current move is finished. Meetings happen at clock times:
+/// - `ConstantFrequency` meetings happen as a Poisson process with the frequency as its rate
+/// - `RegularInterval` meetings happen at every multiple of the interval
+/// - `ScheduledMeetings` happen at the scheduled times
+///
+/// A meeting that costs iterations delays every agent's work by that much time.
+/// ```
+/// use cisat::{Agent, Cohort, EventTeam, Parameters, WorkPace, problems::Ackley};
+/// type S = Ackley<5>;
+/// let parameters = Parameters::default().with_work_pace(WorkPace::Constant {
+///     rates: vec![0.5, 1.0, 2.0],
+/// });
+/// let mut x = Cohort::<S, Agent<S>, EventTeam<S, Agent<S>>>::new(parameters);
+/// x.solve();
+/// ```
+#[derive(Clone, Debug)]
+pub struct EventTeam<S, A = Agent<S>>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// The parameters that the team runs with
+    parameters: Parameters,
+    /// iteration number counter
+    iteration_number: usize,
+    /// The simulated time
+    clock: f64,
+    /// The time at which each agent finishes its current move
+    next_move_times: Vec<f64>,
+    /// The time of the next meeting, which is infinite if there are none left
+    next_meeting_time: f64,
+    /// The agents contained in the team
+    agent_list: Vec<A>,
+    /// Bookkeeping the solution type
+    solution_type: PhantomData<S>,
+    /// The random number generator that all of the team's draws come from
+    rng: StdRng,
+}
+
+impl<S, A> EventTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// Gets the simulated time
+    pub fn get_clock(&self) -> f64 {
+        self.clock
+    }
+
+    /// This draws the time an agent takes for its next move
+    fn draw_move_duration(&mut self, id: usize) -> f64 {
+        match &self.parameters.work_pace {
+            WorkPace::Constant { rates } => 1.0 / rates[id % rates.len()],
+            WorkPace::Exponential { rates } => {
+                let rate = rates[id % rates.len()];
+                random_exponential(rate, &mut self.rng)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// This finds the time of the first meeting after the current time
+    fn draw_next_meeting_time(&mut self) -> f64 {
+        match &self.parameters.communication {
+            CommunicationStyle::ConstantFrequency { frequency } => {
+                let frequency = *frequency;
+                if frequency > 0.0 {
+                    self.clock + random_exponential(frequency, &mut self.rng)
+                } else {
+                    f64::INFINITY
+                }
+            }
+            CommunicationStyle::RegularInterval { interval } => {
+                let interval = *interval as f64;
+                ((self.clock / interval).floor() + 1.0) * interval
+            }
+            CommunicationStyle::ScheduledMeetings { times } => times
+                .iter()
+                .map(|t| *t as f64)
+                .filter(|t| *t > self.clock)
+                .fold(f64::INFINITY, f64::min),
+            _ => f64::INFINITY,
+        }
+    }
+}
+
+impl<S, A> TeamMethods<S, A> for EventTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// This generates a new team
+    fn new(parameters: Parameters) -> Self {
+        // A rate that is not positive and finite would stall the clock, so reject it up front
+        parameters.verify();
+
+        // Seed each agent from the team's random number generator
+        let mut rng = seeded_rng(parameters.seed);
+        let agent_list = (0..parameters.number_of_agents)
+            .map(|i| {
+                A::new(
+                    i,
+                    Parameters {
+                        seed: parameters.seed.map(|_| rng.gen()),
+                        ..parameters.clone()
+                    },
+                )
+            })
+            .collect();
+        let mut team = EventTeam {
+            parameters,
+            iteration_number: 1,
+            clock: 0.0,
+            next_move_times: vec![],
+            next_meeting_time: f64::INFINITY,
+            agent_list,
+            solution_type: Default::default(),
+            rng,
+        };
+
+        // Schedule the first move of every agent, and the first meeting
+        team.next_move_times = (0..team.agent_list.len())
+            .map(|i| team.draw_move_duration(i))
+            .collect();
+        team.next_meeting_time = team.draw_next_meeting_time();
+        team
+    }
+
+    /// This advances the clock by one unit of time, handling every event that happens along the way
+    fn iterate(&mut self) {
+        let end = self.iteration_number as f64;
+        loop {
+            // Find the agent that finishes its move first
+            let (id, move_time) = self.next_move_times.iter().copied().enumerate().fold(
+                (0, f64::INFINITY),
+                |first, (i, t)| {
+                    if t < first.1 {
+                        (i, t)
+                    } else {
+                        first
+                    }
+                },
+            );
+
+            // Meetings go ahead of moves that finish at the same time
+            if self.next_meeting_time <= end && self.next_meeting_time <= move_time {
+                self.clock = self.next_meeting_time;
+                self.communicate();
+            } else if move_time <= end {
+                self.clock = move_time;
+                self.agent_list[id].iterate();
+                restart_stagnating_agents(&mut self.agent_list, &self.parameters);
+                self.next_move_times[id] = move_time + self.draw_move_duration(id);
+            } else {
+                break;
+            }
+        }
+
+        // Advance the clock and increment iteration number
+        self.clock = end;
+        self.iteration_number += 1;
+    }
+
+    fn communicate(&mut self) {
+        // Get the solutions
+        let solutions: Vec<Arc<S>> = self
+            .agent_list
+            .iter()
+            .map(|x| x.get_shared_current_solution())
+            .collect();
+
+        // Share the solutions
+        self.agent_list
+            .iter_mut()
+            .for_each(|x| x.communicate(&solutions));
+
+        // Take time out for the meeting
+        if let MeetingCost::Iterations { iterations } = self.parameters.meeting_cost {
+            self.next_move_times
+                .iter_mut()
+                .for_each(|t| *t += iterations as f64);
+        }
+
+        // Schedule the next meeting
+        self.next_meeting_time = self.draw_next_meeting_time();
+    }
+
+    fn set_parameters(&mut self, parameters: Parameters) {
+        self.agent_list
+            .iter_mut()
+            .for_each(|x| x.set_parameters(parameters.clone()));
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    /// This runs a bunch of units of time to solve, stopping early if the evaluation budget runs out
+    fn solve(&mut self) {
+        self.solve_with_callback(&mut |_| {});
+    }
+
+    /// This solves as `solve` does, calling `after_iteration` after every iteration
+    fn solve_with_callback(&mut self, after_iteration: &mut dyn FnMut(&Self)) {
+        let parameters = self.parameters.clone();
+        solve_within_budget::<S, A, _>(self, &parameters, after_iteration);
+    }
+
+    /// This pulls out the best solution from the team, taking constraint violations into account
+    fn get_best_solution_so_far(&self) -> &S {
+        let constraint_handler = ConstraintHandler::new(&self.parameters.constraint_handling);
+        self.agent_list
+            .iter()
+            .map(|x| x.get_best_solution_so_far())
+            .max_by(|a, b| constraint_handler.compare_best(*a, *b))
+            .unwrap()
+    }
+
+    /// This sums the counters of all agents on the team
+    fn get_counters(&self) -> Counters {
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        self.agent_list
+            .iter()
+            .for_each(|x| counters += &x.get_counters());
+        counters
+    }
+
+    /// This collects the learned matrices of the agents that learn
+    fn get_learned_matrices(&self) -> Vec<LearnedMatrix> {
+        self.agent_list
+            .iter()
+            .filter_map(|x| x.get_learned_matrix())
+            .collect()
+    }
+
+    /// This takes a snapshot of every agent on the team
+    fn get_snapshot(&self) -> TeamSnapshot {
+        TeamSnapshot {
+            iteration: self.iteration_number - 1,
+            agents: self.agent_list.iter().map(|x| x.get_snapshot()).collect(),
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
+        }
+    }
+}
+
+impl<S, A> Default for EventTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    fn default() -> Self {
+        EventTeam::new(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod event_team_tests {
+    use crate::{
+        problems::Ackley, Agent, AgentMethods, CommunicationStyle, EventTeam, Parameters, Solution,
+        TeamMethods, WorkPace,
+    };
+
+    type S = Ackley<5>;
+    type T = EventTeam<S, Agent<S>>;
+
+    #[test]
+    fn test_agents_work_at_their_own_pace() {
+        let parameters = Parameters::default().with_work_pace(WorkPace::Constant {
+            rates: vec![1.0, 2.0, 4.0],
+        });
+        let mut team = T::new(parameters);
+        team.solve();
+        assert_eq!(team.get_clock(), 100.0);
+        let moves: Vec<usize> = team
+            .agent_list
+            .iter()
+            .map(|x| x.get_counters().total_move_applications())
+            .collect();
+        assert_eq!(moves, vec![100, 200, 400]);
+    }
+
+    #[test]
+    fn test_seeded_runs_are_repeatable() {
+        let parameters = Parameters {
+            communication: CommunicationStyle::ConstantFrequency { frequency: 0.2 },
+            ..Parameters::default()
+                .with_seed(42)
+                .with_work_pace(WorkPace::Exponential {
+                    rates: vec![0.5, 1.0, 3.0],
+                })
+        };
+        let best_quality = |parameters: Parameters| {
+            let mut team = T::new(parameters);
+            team.solve();
+            team.get_best_solution_so_far().get_quality_scalar()
+        };
+        assert_eq!(best_quality(parameters.clone()), best_quality(parameters));
+    }
+
+    #[test]
+    #[should_panic(expected = "Work rates must be positive")]
+    fn test_negative_work_rates_are_rejected() {
+        T::new(Parameters::default().with_work_pace(WorkPace::Constant { rates: vec![-1.0] }));
+    }
+}
@@ -0,0 +1,146 @@
+//! This module contains the MixedTeam class, a set of Agents of different kinds
+
+use super::{
+    super::utilities::{
+        parameters::{AgentKind, Parameters},
+        Counters, LearnedMatrix, Solution,
+    },
+    agent::AgentMethods,
+    baselines::{HillClimber, RandomSearch},
+    snapshot::AgentSnapshot,
+    team::Team,
+};
+use crate::Agent;
+use std::sync::Arc;
+
+/// This is a team whose agents can each be of a different kind, as given by
+/// `Parameters::agent_kinds`. The agents are boxed, so that a CISAT agent with Markov learning can
+/// work alongside a greedy hill-climber, for example.
+/// ```
+/// use cisat::{AgentKind, AgentMethods, Cohort, MixedTeam, Parameters, problems::Ackley};
+/// type S = Ackley<5>;
+/// let parameters = Parameters::default()
+///     .with_agent_kinds(vec![AgentKind::Annealing, AgentKind::HillClimbing]);
+/// let mut x = Cohort::<S, Box<dyn AgentMethods<S>>, MixedTeam<S>>::new(parameters);
+/// x.solve();
+/// ```
+pub type MixedTeam<S> = Team<S, Box<dyn AgentMethods<S>>>;
+
+/// Boxed agents pass everything on to the agent inside, which lets other teams, such as `EventTeam`
+/// and `ReplicaTeam`, hold agents of different kinds too
+impl<S: Solution + 'static> AgentMethods<S> for Box<dyn AgentMethods<S>> {
+    /// This generates an agent of the kind given for its slot on the team
+    fn new(id: usize, parameters: Parameters) -> Self {
+        let kind = match parameters.agent_kinds.len() {
+            0 => AgentKind::Annealing,
+            n => parameters.agent_kinds[id % n].clone(),
+        };
+        match kind {
+            AgentKind::Annealing => Box::new(Agent::new(id, parameters)),
+            AgentKind::HillClimbing => Box::new(HillClimber::new(id, parameters)),
+            AgentKind::RandomSearch => Box::new(RandomSearch::new(id, parameters)),
+        }
+    }
+
+    fn iterate(&mut self) {
+        self.as_mut().iterate()
+    }
+
+    fn get_best_solution_so_far(&self) -> &S {
+        self.as_ref().get_best_solution_so_far()
+    }
+
+    fn get_current_solution(&self) -> &S {
+        self.as_ref().get_current_solution()
+    }
+
+    fn get_shared_current_solution(&self) -> Arc<S> {
+        self.as_ref().get_shared_current_solution()
+    }
+
+    fn communicate(&mut self, solutions: &[Arc<S>]) {
+        self.as_mut().communicate(solutions)
+    }
+
+    fn set_current_solution(&mut self, solution: S) {
+        self.as_mut().set_current_solution(solution)
+    }
+
+    fn get_counters(&self) -> Counters {
+        self.as_ref().get_counters()
+    }
+
+    fn is_stagnating(&self) -> bool {
+        self.as_ref().is_stagnating()
+    }
+
+    fn restart_from(&mut self, solution: &S) {
+        self.as_mut().restart_from(solution)
+    }
+
+    fn set_parameters(&mut self, parameters: Parameters) {
+        self.as_mut().set_parameters(parameters)
+    }
+
+    fn get_learned_matrix(&self) -> Option<LearnedMatrix> {
+        self.as_ref().get_learned_matrix()
+    }
+
+    fn get_snapshot(&self) -> AgentSnapshot {
+        self.as_ref().get_snapshot()
+    }
+}
+
+#[cfg(test)]
+mod mixed_team_tests {
+    use crate::{
+        problems::Ackley, AgentKind, AgentMethods, CommunicationStyle, MixedTeam,
+        OperationalLearning, Parameters, ReplicaTeam, Solution, TeamMethods,
+    };
+
+    type S = Ackley<5>;
+
+    #[test]
+    fn test_agents_follow_their_kinds() {
+        let parameters = Parameters {
+            communication: CommunicationStyle::RegularInterval { interval: 5 },
+            operational_learning: OperationalLearning::Markov {
+                learning_rate: 0.05,
+                initial_learning_matrix: vec![],
+            },
+            ..Parameters::default()
+                .with_agents(4)
+                .with_seed(6)
+                .with_agent_kinds(vec![AgentKind::Annealing, AgentKind::HillClimbing])
+        };
+        let mut team = MixedTeam::<S>::new(parameters.clone());
+        team.solve();
+        let temperatures: Vec<bool> = team
+            .get_snapshot()
+            .agents
+            .iter()
+            .map(|x| x.temperature.is_some())
+            .collect();
+        assert_eq!(temperatures, vec![true, false, true, false]);
+        assert_eq!(team.get_learned_matrices().len(), 2);
+
+        // Seeded runs are repeatable, whether or not agents are iterated in parallel
+        let mut other = MixedTeam::<S>::new(parameters.with_parallel_agents(true));
+        other.solve();
+        assert_eq!(
+            team.get_best_solution_so_far().get_quality_scalar(),
+            other.get_best_solution_so_far().get_quality_scalar()
+        );
+
+        // Other teams can hold boxed agents too
+        let mut replicas = ReplicaTeam::<S, Box<dyn AgentMethods<S>>>::new(
+            Parameters::default().with_agent_kinds(vec![AgentKind::RandomSearch]),
+        );
+        replicas.solve();
+        assert!(replicas
+            .get_snapshot()
+            .agents
+            .iter()
+            .all(|x| x.temperature.is_none()));
+    }
+}
@@ -3,8 +3,18 @@
 //! This includes the following `struct`s:
 //! - Agents
 //! - Teams (sets of agents)
+//! - Event-driven teams (sets of agents working at their own pace)
+//! - Replica-exchange teams (sets of agents at a ladder of temperatures)
+//! - Mixed teams (sets of agents of different kinds)
 //! - Cohorts (sets of teams)
+//! - Baseline optimizers to compare against
 
+pub mod adoption;
 pub mod agent;
+pub mod baselines;
 pub mod cohort;
+pub mod event_team;
+pub mod mixed_team;
+pub mod replica_team;
+pub mod snapshot;
 pub mod team;
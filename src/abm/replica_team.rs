@@ -0,0 +1,304 @@
+//! This module contains the ReplicaTeam class, a set of Agents that run at a ladder of fixed
+//! temperatures and swap solutions by replica exchange
+
+use super::{
+    super::utilities::{
+        constraints::ConstraintHandler,
+        parameters::{Parameters, TemperatureSchedule},
+        randomness::seeded_rng,
+        Counters, LearnedMatrix, Solution,
+    },
+    agent::AgentMethods,
+    snapshot::TeamSnapshot,
+    team::{restart_stagnating_agents, solve_within_budget, TeamMethods},
+};
+use crate::Agent;
+use rand::{rngs::StdRng, Rng};
+use rayon::prelude::*;
+use std::marker::PhantomData;
+
+/// This is the ReplicaTeam construct, which runs parallel tempering instead of social
+/// communication. Each agent anneals at a fixed temperature from `Parameters::temperature_ladder`,
+/// and every `Parameters::exchange_interval` iterations, neighboring agents on the ladder offer to
+/// swap their current solutions. A swap between agents at temperatures `t_i` and `t_j` with
+/// qualities `q_i` and `q_j` is accepted with probability
+/// `min(1, exp((1 / t_i - 1 / t_j) * (q_j - q_i)))`, which moves better solutions toward colder
+/// agents. Alternate exchanges pair up agents starting from the first and the second.
+/// ```
+/// use cisat::{Agent, Cohort, Parameters, ReplicaTeam, TemperatureLadder, problems::Ackley};
+/// type S = Ackley<5>;
+/// let parameters = Parameters::default()
+///     .with_agents(4)
+///     .with_temperature_ladder(TemperatureLadder::Geometric {
+///         lowest: 0.01,
+///         highest: 1.0,
+///     })
+///     .with_exchange_interval(5);
+/// let mut x = Cohort::<S, Agent<S>, ReplicaTeam<S, Agent<S>>>::new(parameters);
+/// x.solve();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReplicaTeam<S, A = Agent<S>>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// The parameters that the team runs with
+    parameters: Parameters,
+    /// iteration number counter
+    iteration_number: usize,
+    /// The temperature of each agent
+    temperatures: Vec<f64>,
+    /// The number of exchanges attempted so far
+    exchanges_attempted: usize,
+    /// The number of exchanges accepted so far
+    exchanges_accepted: usize,
+    /// The agents contained in the team, in order of increasing temperature
+    agent_list: Vec<A>,
+    /// Bookkeeping the solution type
+    solution_type: PhantomData<S>,
+    /// The random number generator that all of the team's draws come from
+    rng: StdRng,
+}
+
+impl<S, A> ReplicaTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// Gets the temperature of each agent
+    pub fn get_temperatures(&self) -> &[f64] {
+        &self.temperatures
+    }
+
+    /// Gets the number of exchanges attempted and the number accepted so far
+    pub fn get_exchange_counts(&self) -> (usize, usize) {
+        (self.exchanges_attempted, self.exchanges_accepted)
+    }
+}
+
+impl<S, A> TeamMethods<S, A> for ReplicaTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    /// This generates a new team, with each agent fixed at its temperature on the ladder
+    fn new(parameters: Parameters) -> Self {
+        // An empty ladder or an exchange interval of 0 would panic mid-run, so reject them up front
+        parameters.verify();
+
+        // Seed each agent from the team's random number generator
+        let mut rng = seeded_rng(parameters.seed);
+        let temperatures = parameters
+            .temperature_ladder
+            .get_temperatures(parameters.number_of_agents);
+        ReplicaTeam {
+            agent_list: temperatures
+                .iter()
+                .enumerate()
+                .map(|(i, temperature)| {
+                    A::new(
+                        i,
+                        Parameters {
+                            temperature_schedule: TemperatureSchedule::Fixed {
+                                temperature: *temperature,
+                            },
+                            seed: parameters.seed.map(|_| rng.gen()),
+                            ..parameters.clone()
+                        },
+                    )
+                })
+                .collect(),
+            parameters,
+            iteration_number: 1,
+            temperatures,
+            exchanges_attempted: 0,
+            exchanges_accepted: 0,
+            solution_type: Default::default(),
+            rng,
+        }
+    }
+
+    /// This runs a single iteration
+    fn iterate(&mut self) {
+        // Check if its time to exchange
+        if self
+            .iteration_number
+            .is_multiple_of(self.parameters.exchange_interval)
+        {
+            self.communicate();
+        }
+
+        // Then iterate the agents
+        if self.parameters.parallel_agents {
+            self.agent_list.par_iter_mut().for_each(|x| x.iterate());
+        } else {
+            self.agent_list.iter_mut().for_each(|x| x.iterate());
+        }
+
+        // Restart agents that have stagnated from the team's best solution
+        restart_stagnating_agents(&mut self.agent_list, &self.parameters);
+
+        // Increment iteration number
+        self.iteration_number += 1;
+    }
+
+    /// This offers a swap to each pair of neighbors on the ladder
+    fn communicate(&mut self) {
+        let constraint_handler = ConstraintHandler::new(&self.parameters.constraint_handling);
+        let first = (self.iteration_number / self.parameters.exchange_interval + 1) % 2;
+        for i in (first..self.agent_list.len().saturating_sub(1)).step_by(2) {
+            let j = i + 1;
+            let quality_i =
+                constraint_handler.penalized_quality(self.agent_list[i].get_current_solution());
+            let quality_j =
+                constraint_handler.penalized_quality(self.agent_list[j].get_current_solution());
+            let delta =
+                (1.0 / self.temperatures[i] - 1.0 / self.temperatures[j]) * (quality_j - quality_i);
+            self.exchanges_attempted += 1;
+            if delta >= 0.0 || self.rng.gen::<f64>() < delta.exp() {
+                let solution_i = self.agent_list[i].get_current_solution().clone();
+                let solution_j = self.agent_list[j].get_current_solution().clone();
+                self.agent_list[i].set_current_solution(solution_j);
+                self.agent_list[j].set_current_solution(solution_i);
+                self.exchanges_accepted += 1;
+            }
+        }
+    }
+
+    /// This changes the parameters from now on, keeping every agent at its temperature on the
+    /// ladder it was built with
+    fn set_parameters(&mut self, parameters: Parameters) {
+        for (agent, temperature) in self.agent_list.iter_mut().zip(self.temperatures.iter()) {
+            agent.set_parameters(Parameters {
+                temperature_schedule: TemperatureSchedule::Fixed {
+                    temperature: *temperature,
+                },
+                ..parameters.clone()
+            });
+        }
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    /// This runs a bunch of iterations to solve, stopping early if the evaluation budget runs out
+    fn solve(&mut self) {
+        self.solve_with_callback(&mut |_| {});
+    }
+
+    /// This solves as `solve` does, calling `after_iteration` after every iteration
+    fn solve_with_callback(&mut self, after_iteration: &mut dyn FnMut(&Self)) {
+        let parameters = self.parameters.clone();
+        solve_within_budget::<S, A, _>(self, &parameters, after_iteration);
+    }
+
+    /// This pulls out the best solution from the team, taking constraint violations into account
+    fn get_best_solution_so_far(&self) -> &S {
+        let constraint_handler = ConstraintHandler::new(&self.parameters.constraint_handling);
+        self.agent_list
+            .iter()
+            .map(|x| x.get_best_solution_so_far())
+            .max_by(|a, b| constraint_handler.compare_best(*a, *b))
+            .unwrap()
+    }
+
+    /// This sums the counters of all agents on the team
+    fn get_counters(&self) -> Counters {
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        self.agent_list
+            .iter()
+            .for_each(|x| counters += &x.get_counters());
+        counters
+    }
+
+    /// This collects the learned matrices of the agents that learn
+    fn get_learned_matrices(&self) -> Vec<LearnedMatrix> {
+        self.agent_list
+            .iter()
+            .filter_map(|x| x.get_learned_matrix())
+            .collect()
+    }
+
+    /// This takes a snapshot of every agent on the team
+    fn get_snapshot(&self) -> TeamSnapshot {
+        TeamSnapshot {
+            iteration: self.iteration_number - 1,
+            agents: self.agent_list.iter().map(|x| x.get_snapshot()).collect(),
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
+        }
+    }
+}
+
+impl<S, A> Default for ReplicaTeam<S, A>
+where
+    S: Solution,
+    A: AgentMethods<S>,
+{
+    fn default() -> Self {
+        ReplicaTeam::new(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod replica_team_tests {
+    use crate::{
+        problems::Ackley, Agent, Parameters, ReplicaTeam, Solution, TeamMethods, TemperatureLadder,
+    };
+
+    type S = Ackley<5>;
+    type T = ReplicaTeam<S, Agent<S>>;
+
+    #[test]
+    fn test_agents_run_on_the_ladder_and_exchange() {
+        let parameters = Parameters::default()
+            .with_agents(4)
+            .with_seed(12)
+            .with_temperature_ladder(TemperatureLadder::Geometric {
+                lowest: 0.001,
+                highest: 1.0,
+            })
+            .with_exchange_interval(1);
+        let mut team = T::new(parameters.clone());
+        team.solve();
+        let temperatures: Vec<_> = team
+            .get_snapshot()
+            .agents
+            .iter()
+            .map(|x| x.temperature.unwrap())
+            .collect();
+        assert_eq!(temperatures, team.get_temperatures());
+        assert!((temperatures[1] - 0.01).abs() < 1e-12);
+
+        // Exchanges alternate between the two sets of neighbors, which gives three pairs over two
+        // iterations with four agents
+        let (attempted, accepted) = team.get_exchange_counts();
+        assert_eq!(attempted, 50 * 3);
+        assert!(accepted > 0 && accepted < attempted);
+
+        // Seeded runs are repeatable
+        let mut other = T::new(parameters);
+        other.solve();
+        assert_eq!(
+            team.get_best_solution_so_far().get_quality_scalar(),
+            other.get_best_solution_so_far().get_quality_scalar()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The exchange interval must be at least 1.")]
+    fn test_exchange_interval_of_zero_is_rejected() {
+        T::new(Parameters::default().with_exchange_interval(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "there must be at least one")]
+    fn test_empty_ladder_is_rejected() {
+        T::new(
+            Parameters::default().with_temperature_ladder(TemperatureLadder::Custom {
+                temperatures: vec![],
+            }),
+        );
+    }
+}
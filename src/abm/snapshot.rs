@@ -0,0 +1,43 @@
+//! This module contains lightweight snapshots of the state of agents, teams and cohorts, which
+//! make it possible to follow a run step by step
+
+/// This is a snapshot of the state of an agent
+#[derive(Clone, Debug, PartialEq)]
+pub struct AgentSnapshot {
+    /// The quality of the agent's current solution
+    pub current_quality: f64,
+    /// The quality of the best solution the agent has found
+    pub best_quality: f64,
+    /// The agent's temperature, if it has one
+    pub temperature: Option<f64>,
+    /// The step size the agent hands to move operators, if it has one
+    pub step_size: Option<f64>,
+    /// The move operator the agent applied most recently, if any
+    pub last_operation: Option<usize>,
+    /// The number of times the agent has been reheated after stagnating
+    pub reheats: usize,
+    /// The number of times the agent has restarted after stagnating
+    pub restarts: usize,
+}
+
+/// This is a snapshot of the state of a team
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeamSnapshot {
+    /// The number of iterations the team has completed
+    pub iteration: usize,
+    /// The state of each agent, indexed by agent id
+    pub agents: Vec<AgentSnapshot>,
+    /// The quality of the best solution the team has found
+    pub best_quality: f64,
+}
+
+/// This is a snapshot of the state of a cohort
+#[derive(Clone, Debug, PartialEq)]
+pub struct CohortSnapshot {
+    /// The number of iterations the cohort has been stepped through
+    pub iteration: usize,
+    /// The state of each team
+    pub teams: Vec<TeamSnapshot>,
+    /// The quality of the best solution any team has found
+    pub best_quality: f64,
+}
@@ -1,11 +1,19 @@
 //! This module contains the Team class, a set of interacting Agents
 
 use super::{
-    super::utilities::{parameters::Parameters, randomness::random_unit_draw, Solution},
+    super::utilities::{
+        constraints::ConstraintHandler,
+        parameters::{MeetingCost, Parameters},
+        randomness::seeded_rng,
+        Counters, LearnedMatrix, Solution,
+    },
     agent::AgentMethods,
+    snapshot::TeamSnapshot,
 };
 use crate::{Agent, CommunicationStyle};
-use std::marker::PhantomData;
+use rand::{rngs::StdRng, Rng};
+use rayon::prelude::*;
+use std::{marker::PhantomData, sync::Arc};
 
 /// This is the Team construct, which contains a set of Agents
 #[derive(Clone, Debug)]
@@ -20,8 +28,12 @@ where
     iteration_number: usize,
     /// The agents contained in the team
     agent_list: Vec<A>,
+    /// The number of iterations that the agents still have to spend in a meeting
+    meeting_iterations_remaining: usize,
     /// Bookkeeping the solution type
     solution_type: PhantomData<S>,
+    /// The random number generator that all of the team's draws come from
+    rng: StdRng,
 }
 
 /// This is a trait for implementing new teams
@@ -32,10 +44,52 @@ pub trait TeamMethods<S: Solution, A: AgentMethods<S> = Agent<S>>: Send {
     fn iterate(&mut self);
     /// Tell the team to talk
     fn communicate(&mut self);
+    /// Changes the parameters the team and its agents run with from now on. The seed and the number
+    /// of agents are only used to build the team, so changing them has no effect. By default, the
+    /// team keeps its old parameters.
+    fn set_parameters(&mut self, _parameters: Parameters) {}
     /// Solves all the way for a solution
     fn solve(&mut self);
+    /// Solves all the way for a solution, calling `after_iteration` with the team after every
+    /// iteration. By default, this calls `solve` and then `after_iteration` once, so teams with
+    /// their own `solve` should override this to report progress as they go.
+    fn solve_with_callback(&mut self, after_iteration: &mut dyn FnMut(&Self))
+    where
+        Self: Sized,
+    {
+        self.solve();
+        after_iteration(self);
+    }
     /// Gets the best solution found by the team so far
-    fn get_best_solution_so_far(&mut self) -> S;
+    fn get_best_solution_so_far(&self) -> &S;
+    /// Gets the counts of evaluations, move applications and communication summed over the team. By
+    /// default, nothing is counted.
+    fn get_counters(&self) -> Counters {
+        Counters::new(S::NUMBER_OF_MOVE_OPERATORS)
+    }
+    /// Gets the move operator preferences learned by each agent on the team that learns any. By
+    /// default, there are none.
+    fn get_learned_matrices(&self) -> Vec<LearnedMatrix> {
+        vec![]
+    }
+    /// Gets the average of the move operator preferences learned by the team, if any were learned
+    fn get_average_learned_matrix(&self) -> Option<LearnedMatrix> {
+        LearnedMatrix::average(&self.get_learned_matrices()).ok()
+    }
+    /// Gets a snapshot of the team's state. By default, this has the best quality found so far, but
+    /// no agents and an iteration of 0.
+    fn get_snapshot(&self) -> TeamSnapshot {
+        TeamSnapshot {
+            iteration: 0,
+            agents: vec![],
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
+        }
+    }
+    /// Runs a single iteration and returns a snapshot of the team's state afterwards
+    fn step(&mut self) -> TeamSnapshot {
+        self.iterate();
+        self.get_snapshot()
+    }
 }
 
 impl<S, A> TeamMethods<S, A> for Team<S, A>
@@ -45,22 +99,43 @@ where
 {
     /// This generates a new team
     fn new(parameters: Parameters) -> Self {
+        parameters.verify();
+
+        // Seed each agent from the team's random number generator
+        let mut rng = seeded_rng(parameters.seed);
         Team {
             agent_list: (0..parameters.number_of_agents)
-                .map(|i| A::new(i, parameters.clone()))
+                .map(|i| {
+                    A::new(
+                        i,
+                        Parameters {
+                            seed: parameters.seed.map(|_| rng.gen()),
+                            ..parameters.clone()
+                        },
+                    )
+                })
                 .collect(),
             parameters,
             iteration_number: 1,
+            meeting_iterations_remaining: 0,
             solution_type: Default::default(),
+            rng,
         }
     }
 
     /// This runs a single iteration
     fn iterate(&mut self) {
+        // Agents still in a meeting do no work
+        if self.meeting_iterations_remaining > 0 {
+            self.meeting_iterations_remaining -= 1;
+            self.iteration_number += 1;
+            return;
+        }
+
         // Check if its time to interact
         match &self.parameters.communication {
             CommunicationStyle::ConstantFrequency { frequency } => {
-                if random_unit_draw() < *frequency {
+                if self.rng.gen::<f64>() < *frequency {
                     self.communicate();
                 }
             }
@@ -77,8 +152,17 @@ where
             CommunicationStyle::None => {}
         }
 
-        // Then iterate the agents
-        self.agent_list.iter_mut().for_each(|x| x.iterate());
+        // Then iterate the agents, unless the meeting took up this iteration
+        if self.meeting_iterations_remaining > 0 {
+            self.meeting_iterations_remaining -= 1;
+        } else if self.parameters.parallel_agents {
+            self.agent_list.par_iter_mut().for_each(|x| x.iterate());
+        } else {
+            self.agent_list.iter_mut().for_each(|x| x.iterate());
+        }
+
+        // Restart agents that have stagnated from the team's best solution
+        restart_stagnating_agents(&mut self.agent_list, &self.parameters);
 
         // Increment iteration number
         self.iteration_number += 1;
@@ -86,33 +170,118 @@ where
 
     fn communicate(&mut self) {
         // Get the solutions
-        let solutions: Vec<S> = self
+        let solutions: Vec<Arc<S>> = self
             .agent_list
-            .iter_mut()
-            .map(|x| x.get_current_solution())
+            .iter()
+            .map(|x| x.get_shared_current_solution())
             .collect();
 
         // Share the solutions
         self.agent_list
             .iter_mut()
-            .for_each(|x| x.communicate(solutions.clone()));
+            .for_each(|x| x.communicate(&solutions));
+
+        // Take time out for the meeting
+        if let MeetingCost::Iterations { iterations } = self.parameters.meeting_cost {
+            self.meeting_iterations_remaining = iterations;
+        }
     }
 
-    /// This runs a bunch of iterations to solve
+    fn set_parameters(&mut self, parameters: Parameters) {
+        self.agent_list
+            .iter_mut()
+            .for_each(|x| x.set_parameters(parameters.clone()));
+        self.parameters = Parameters {
+            seed: self.parameters.seed,
+            ..parameters
+        };
+    }
+
+    /// This runs a bunch of iterations to solve, stopping early if the evaluation budget runs out
     fn solve(&mut self) {
-        for _ in 0..self.parameters.number_of_iterations {
-            self.iterate();
+        self.solve_with_callback(&mut |_| {});
+    }
+
+    /// This solves as `solve` does, calling `after_iteration` after every iteration
+    fn solve_with_callback(&mut self, after_iteration: &mut dyn FnMut(&Self)) {
+        let parameters = self.parameters.clone();
+        solve_within_budget::<S, A, _>(self, &parameters, after_iteration);
+    }
+
+    /// This pulls out the best solution from the team, taking constraint violations into account
+    fn get_best_solution_so_far(&self) -> &S {
+        let constraint_handler = ConstraintHandler::new(&self.parameters.constraint_handling);
+        self.agent_list
+            .iter()
+            .map(|x| x.get_best_solution_so_far())
+            .max_by(|a, b| constraint_handler.compare_best(*a, *b))
+            .unwrap()
+    }
+
+    /// This sums the counters of all agents on the team
+    fn get_counters(&self) -> Counters {
+        let mut counters = Counters::new(S::NUMBER_OF_MOVE_OPERATORS);
+        self.agent_list
+            .iter()
+            .for_each(|x| counters += &x.get_counters());
+        counters
+    }
+
+    /// This collects the learned matrices of the agents that learn
+    fn get_learned_matrices(&self) -> Vec<LearnedMatrix> {
+        self.agent_list
+            .iter()
+            .filter_map(|x| x.get_learned_matrix())
+            .collect()
+    }
+
+    /// This takes a snapshot of every agent on the team
+    fn get_snapshot(&self) -> TeamSnapshot {
+        TeamSnapshot {
+            iteration: self.iteration_number - 1,
+            agents: self.agent_list.iter().map(|x| x.get_snapshot()).collect(),
+            best_quality: self.get_best_solution_so_far().get_quality_scalar(),
         }
     }
+}
 
-    /// This pulls out the best solution from the team
-    fn get_best_solution_so_far(&mut self) -> S {
-        (0..self.parameters.number_of_agents)
-            .map(|i| self.agent_list[i].get_best_solution_so_far())
-            .collect::<Vec<S>>()
-            .into_iter()
-            .max()
+/// This iterates a team until it has run for the number of iterations in `parameters` or used up
+/// its evaluation budget, calling `after_iteration` after every iteration
+pub(super) fn solve_within_budget<S, A, T>(
+    team: &mut T,
+    parameters: &Parameters,
+    after_iteration: &mut dyn FnMut(&T),
+) where
+    S: Solution,
+    A: AgentMethods<S>,
+    T: TeamMethods<S, A>,
+{
+    for _ in 0..parameters.number_of_iterations {
+        if parameters.is_budget_exhausted(&team.get_counters()) {
+            break;
+        }
+        team.iterate();
+        after_iteration(team);
+    }
+}
+
+/// This restarts any stagnating agents from the best solution on their team
+pub(super) fn restart_stagnating_agents<S: Solution, A: AgentMethods<S>>(
+    agent_list: &mut [A],
+    parameters: &Parameters,
+) {
+    if agent_list.iter().any(|x| x.is_stagnating()) {
+        let constraint_handler = ConstraintHandler::new(&parameters.constraint_handling);
+        let best = agent_list
+            .iter()
+            .map(|x| x.get_best_solution_so_far())
+            .max_by(|a, b| constraint_handler.compare_best(*a, *b))
             .unwrap()
+            .clone();
+        agent_list
+            .iter_mut()
+            .filter(|x| x.is_stagnating())
+            .for_each(|x| x.restart_from(&best));
     }
 }
 
@@ -125,3 +294,169 @@ where
         Team::new(Default::default())
     }
 }
+
+#[cfg(test)]
+mod team_tests {
+    use crate::{
+        problems::{Ackley, Sphere},
+        Agent, CommunicationStyle, MeetingCost, Parameters, QualityNormalization, Solution,
+        StagnationResponse, Team, TeamMethods,
+    };
+
+    type S = Ackley<5>;
+    type T = Team<S, Agent<S>>;
+
+    /// This solves a team with the given parameters and returns the best quality it found
+    fn best_quality(parameters: Parameters) -> f64 {
+        let mut team = T::new(parameters);
+        team.solve();
+        team.get_best_solution_so_far().get_quality_scalar()
+    }
+
+    #[test]
+    fn test_seeded_runs_are_repeatable() {
+        let parameters = Parameters::default().with_seed(42);
+        assert_eq!(best_quality(parameters.clone()), best_quality(parameters));
+    }
+
+    #[test]
+    fn test_parallel_agents_are_deterministic() {
+        let parameters = Parameters {
+            communication: CommunicationStyle::ConstantFrequency { frequency: 0.2 },
+            ..Parameters::default().with_agents(8).with_seed(42)
+        };
+        assert_eq!(
+            best_quality(parameters.clone()),
+            best_quality(parameters.with_parallel_agents(true))
+        );
+    }
+
+    #[test]
+    fn test_communication_with_negative_qualities() {
+        for quality_normalization in &[
+            QualityNormalization::MinShift,
+            QualityNormalization::RankBased,
+            QualityNormalization::Softmax { temperature: 0.5 },
+            QualityNormalization::None,
+        ] {
+            // Raw qualities need a quality bias that outweighs the most negative quality
+            let quality_bias = match quality_normalization {
+                QualityNormalization::None => 200.0,
+                _ => 1.0,
+            };
+            let parameters = Parameters {
+                communication: CommunicationStyle::RegularInterval { interval: 1 },
+                quality_bias,
+                ..Parameters::default()
+                    .with_seed(11)
+                    .with_quality_normalization(quality_normalization.clone())
+            };
+            let mut team = Team::<Sphere<5>, Agent<Sphere<5>>>::new(parameters.clone());
+            team.solve();
+            assert!(team.get_best_solution_so_far().get_quality_scalar() < 0.0);
+            assert!(team.get_counters().adoptions > 0);
+
+            // Without the bias, raw negative qualities carry no weight and nothing is adopted
+            if let QualityNormalization::None = quality_normalization {
+                let mut team = Team::<Sphere<5>, Agent<Sphere<5>>>::new(Parameters {
+                    quality_bias: 1.0,
+                    ..parameters
+                });
+                team.solve();
+                assert_eq!(team.get_counters().adoptions, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crossover_during_communication() {
+        let parameters = Parameters {
+            communication: CommunicationStyle::RegularInterval { interval: 1 },
+            ..Parameters::default().with_seed(5).with_crossover_rate(1.0)
+        };
+        let mut team = T::new(parameters);
+        team.solve();
+        let counters = team.get_counters();
+        assert!(counters.crossovers > 0);
+        assert_eq!(counters.crossovers, counters.adoptions);
+    }
+
+    #[test]
+    fn test_meetings_cost_time() {
+        // Meetings at every fourth iteration take up that iteration and the next
+        let parameters = Parameters {
+            communication: CommunicationStyle::RegularInterval { interval: 4 },
+            ..Parameters::default().with_meeting_cost(MeetingCost::Iterations { iterations: 2 })
+        };
+        let mut team = T::new(parameters);
+        team.solve();
+        assert_eq!(team.get_counters().total_move_applications(), 3 * 51);
+
+        // Meetings that cost evaluations are charged to every participant
+        let parameters = Parameters {
+            communication: CommunicationStyle::RegularInterval { interval: 4 },
+            ..Parameters::default().with_meeting_cost(MeetingCost::Evaluations { evaluations: 2 })
+        };
+        let mut team = T::new(parameters);
+        team.solve();
+        assert_eq!(team.get_counters().meeting_evaluations, 25 * 3 * 2);
+    }
+
+    #[test]
+    fn test_stepping_matches_solving() {
+        let parameters = Parameters::default().with_seed(8);
+        let mut stepped = T::new(parameters.clone());
+        let snapshots: Vec<_> = (0..parameters.number_of_iterations)
+            .map(|_| stepped.step())
+            .collect();
+        let last = snapshots.last().unwrap();
+        assert_eq!(last.iteration, parameters.number_of_iterations);
+        assert_eq!(last.agents.len(), parameters.number_of_agents);
+        assert_eq!(last.best_quality, best_quality(parameters));
+    }
+
+    #[test]
+    fn test_stagnating_agents_are_reheated_or_restarted() {
+        let run = |stagnation_response: StagnationResponse| {
+            let mut team = T::new(
+                Parameters::default()
+                    .with_seed(9)
+                    .with_stagnation_response(stagnation_response, 5),
+            );
+            team.solve();
+
+            // Each agent's snapshot shows its own share of the stagnation events
+            let agents = team.get_snapshot().agents;
+            let counters = team.get_counters();
+            assert_eq!(
+                agents.iter().map(|x| x.reheats).sum::<usize>(),
+                counters.reheats
+            );
+            assert_eq!(
+                agents.iter().map(|x| x.restarts).sum::<usize>(),
+                counters.restarts
+            );
+            counters
+        };
+        assert!(run(StagnationResponse::Reheat { fraction: 0.5 }).reheats > 0);
+        let counters = run(StagnationResponse::Restart);
+        assert!(counters.restarts > 0);
+        assert_eq!(
+            counters.evaluations,
+            counters.total_move_applications() + 3 + counters.restarts
+        );
+        let counters = run(StagnationResponse::RestartFromTeamBest);
+        assert!(counters.restarts > 0);
+        assert_eq!(counters.evaluations, counters.total_move_applications() + 3);
+        assert_eq!(run(StagnationResponse::None).restarts, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The reheating fraction must be greater than 0 and at most 1.")]
+    fn test_negative_reheating_fraction_is_rejected() {
+        T::new(
+            Parameters::default()
+                .with_stagnation_response(StagnationResponse::Reheat { fraction: -0.5 }, 5),
+        );
+    }
+}
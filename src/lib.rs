@@ -7,14 +7,25 @@
 //! (CISAT) framework.
 
 mod utilities;
+pub use cisat_derive::CisatSolution;
 pub use utilities::{
-    parameters::{CommunicationStyle, OperationalLearning, Parameters, TemperatureSchedule},
-    Solution,
+    parameters::{
+        AdoptionStyle, AgentKind, CandidateSelection, CommunicationStyle, ConstraintHandling,
+        MeetingCost, OperationalLearning, Parameters, QualityNormalization, StagnationResponse,
+        StepSizeAdaptation, TemperatureLadder, TemperatureSchedule, WorkPace,
+    },
+    Counters, LearnedMatrix, Solution,
 };
 
 mod abm;
+pub use abm::adoption::AdoptionStrategy;
 pub use abm::agent::{Agent, AgentMethods};
-pub use abm::cohort::Cohort;
+pub use abm::baselines::{EvolutionaryTeam, HillClimber, RandomSearch, SoloTeam};
+pub use abm::cohort::{Cohort, Progress};
+pub use abm::event_team::EventTeam;
+pub use abm::mixed_team::MixedTeam;
+pub use abm::replica_team::ReplicaTeam;
+pub use abm::snapshot::{AgentSnapshot, CohortSnapshot, TeamSnapshot};
 pub use abm::team::{Team, TeamMethods};
 
 pub mod problems;
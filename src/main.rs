@@ -1,10 +1,16 @@
 use cisat::{
-    problems::{Ackley, Structure},
-    AgentMethods, Cohort, CommunicationStyle, OperationalLearning, Parameters, Solution,
-    TeamMethods, TemperatureSchedule,
+    problems::{
+        Ackley, Griewank, Knapsack, KnapsackInstance, Levy, Michalewicz, Rastrigin, Rosenbrock,
+        Schwefel, SetCover, SetCoverInstance, Sphere, Structure, StyblinskiTang, Tsp, TspInstance,
+    },
+    AdoptionStyle, Agent, AgentKind, AgentMethods, CandidateSelection, Cohort, CommunicationStyle,
+    ConstraintHandling, EventTeam, EvolutionaryTeam, HillClimber, LearnedMatrix, MeetingCost,
+    OperationalLearning, Parameters, QualityNormalization, RandomSearch, ReplicaTeam, SoloTeam,
+    Solution, StagnationResponse, StepSizeAdaptation, Team, TeamMethods, TemperatureLadder,
+    TemperatureSchedule, WorkPace,
 };
 use indicatif::{HumanDuration, ProgressBar, ProgressStyle};
-
+use log::{Level, LevelFilter, Log, Metadata, Record};
 use std::time::Instant;
 use structopt::StructOpt;
 
@@ -21,9 +27,17 @@ struct Cli {
     /// The number of teams to run
     #[structopt(short = "T", long, default_value = "10")]
     teams: usize,
-    /// The problem to simulate solving (Ackley or Structure)
+    /// The problem to simulate solving (Ackley, Sphere, Rastrigin, Rosenbrock, Schwefel, Griewank,
+    /// Levy, Michalewicz, StyblinskiTang, Tsp, Knapsack, SetCover or Structure)
     #[structopt(short = "P", long)]
     problem: String,
+    /// A file to load the instance from, in TSPLIB format for Tsp or OR-Library format for
+    /// Knapsack and SetCover
+    #[structopt(long)]
+    instance: Option<String>,
+    /// Which problem to load from an OR-Library knapsack file holding several, counting from zero
+    #[structopt(long, default_value = "0")]
+    problem_index: usize,
     /// The number of agents on each team
     #[structopt(short = "A", long, default_value = "3")]
     agents: usize,
@@ -50,18 +64,172 @@ struct Cli {
     /// The temperature schedule to use (Geometric, Cauchy, or Triki)
     #[structopt(short = "r", long, default_value = "0.05")]
     pub learning_rate: f64,
+    /// A file of learned matrices, as written by --export-learning, whose average the agents start
+    /// learning from
+    #[structopt(long)]
+    pub import_learning: Option<String>,
+    /// A file to write the matrices learned by every agent to
+    #[structopt(long)]
+    pub export_learning: Option<String>,
+    /// The number of candidate solutions each agent generates per iteration
+    #[structopt(long, default_value = "1")]
+    pub candidates: usize,
+    /// How agents choose among their candidates (Best or Boltzmann)
+    #[structopt(long, default_value = "Best")]
+    pub candidate_selection: String,
+    /// Generate each candidate with a different move operator where possible
+    #[structopt(long)]
+    pub mix_operators: bool,
+    /// How agents adapt the step size of their moves (OneFifthRule, AcceptanceRate, or None)
+    #[structopt(long, default_value = "None")]
+    pub step_size: String,
+    /// The number of iterations between step size adaptations
+    #[structopt(long, default_value = "10")]
+    pub step_size_window: usize,
+    /// The factor that step sizes grow or shrink by
+    #[structopt(long, default_value = "1.5")]
+    pub step_size_factor: f64,
+    /// The acceptance rate that step sizes are adapted toward
+    #[structopt(long, default_value = "0.44")]
+    pub target_acceptance: f64,
+    /// What agents do when they stagnate (Reheat, Restart, RestartFromTeamBest, or None)
+    #[structopt(long, default_value = "None")]
+    pub stagnation: String,
+    /// The number of iterations without improvement after which agents respond to stagnation
+    #[structopt(long, default_value = "20")]
+    pub stagnation_iterations: usize,
+    /// The fraction of the initial temperature that stagnating agents are reheated to
+    #[structopt(long, default_value = "0.5")]
+    pub reheat_fraction: f64,
+    /// When agents communicate (ConstantFrequency, RegularInterval, ScheduledMeetings, or None)
+    #[structopt(long, default_value = "None")]
+    pub communication: String,
+    /// The number of iterations between meetings for regular interval communication
+    #[structopt(long, default_value = "10")]
+    pub interval: usize,
+    /// The chance of a meeting in each iteration for constant frequency communication
+    #[structopt(long, default_value = "0.1")]
+    pub frequency: f64,
+    /// The iterations at which scheduled meetings happen
+    #[structopt(long, use_delimiter = true)]
+    pub meeting_times: Vec<usize>,
+    /// The number of iterations that each meeting takes up
+    #[structopt(long, conflicts_with = "meeting-evaluations")]
+    pub meeting_iterations: Option<usize>,
+    /// The number of evaluations that each meeting costs each participant
+    #[structopt(long)]
+    pub meeting_evaluations: Option<usize>,
     /// The self bias value to use
     #[structopt(short = "b", long, default_value = "1.0")]
     pub self_bias: f64,
     /// The quality bias value to use
     #[structopt(short = "q", long, default_value = "1.0")]
     pub quality_bias: f64,
+    /// The quality normalization to use when communicating (MinShift, RankBased, Softmax, or None)
+    #[structopt(short = "N", long, default_value = "MinShift")]
+    pub normalization: String,
+    /// The temperature for softmax quality normalization
+    #[structopt(long, default_value = "1.0")]
+    pub softmax_temperature: f64,
+    /// The rule agents use to adopt solutions (WeightedDraw, GreedyBest, Tournament, Boltzmann,
+    /// OnlyIfBetter, or RandomPeer)
+    #[structopt(long, default_value = "WeightedDraw")]
+    pub adoption: String,
+    /// The number of solutions drawn for tournament adoption
+    #[structopt(long, default_value = "2")]
+    pub tournament_size: usize,
+    /// The temperature for Boltzmann adoption
+    #[structopt(long, default_value = "1.0")]
+    pub adoption_temperature: f64,
+    /// The probability of recombining a teammate's solution rather than adopting it wholesale
+    #[structopt(short = "X", long, default_value = "0.0")]
+    pub crossover_rate: f64,
     /// The satisficing fraction to use
     #[structopt(short = "s", long, default_value = "0.5")]
     pub satisficing: f64,
+    /// The constraint handling style to use (StaticPenalty, AdaptivePenalty, FeasibilityFirst,
+    /// StochasticRanking, or None)
+    #[structopt(short = "C", long, default_value = "None")]
+    pub constraints: String,
+    /// The penalty coefficient for constraint violations, or its initial value if adaptive
+    #[structopt(long, default_value = "1.0")]
+    pub penalty: f64,
+    /// The probability that stochastic ranking compares infeasible solutions by quality
+    #[structopt(long, default_value = "0.45")]
+    pub ranking_probability: f64,
+    /// The maximum number of evaluations each team may use
+    #[structopt(short = "B", long)]
+    pub budget: Option<usize>,
+    /// The seed for random number generation
+    #[structopt(long)]
+    pub seed: Option<u64>,
+    /// Iterate agents within each team in parallel
+    #[structopt(long)]
+    pub parallel_agents: bool,
+    /// Iterate teams in parallel when running without --parallel
+    #[structopt(long)]
+    pub parallel_teams: bool,
+    /// The number of threads to run teams on
+    #[structopt(long)]
+    pub threads: Option<usize>,
+    /// Simulate time on a shared clock, with agents working at their own pace
+    #[structopt(long)]
+    pub event_driven: bool,
+    /// The number of moves each agent makes per unit of time, reused from the start of the list
+    /// if there are more agents than rates
+    #[structopt(long, use_delimiter = true)]
+    pub work_rates: Vec<f64>,
+    /// Draw the time each move takes from an exponential distribution with the agent's rate
+    #[structopt(long)]
+    pub exponential_work: bool,
+    /// Run each team as parallel tempering, with agents swapping solutions across a temperature
+    /// ladder
+    #[structopt(long, conflicts_with = "event-driven")]
+    pub replica_exchange: bool,
+    /// The temperature of the coldest agent on the ladder
+    #[structopt(long, default_value = "0.01")]
+    pub lowest_temperature: f64,
+    /// The temperature of the hottest agent on the ladder
+    #[structopt(long, default_value = "1.0")]
+    pub highest_temperature: f64,
+    /// The number of iterations between replica exchanges
+    #[structopt(long, default_value = "10")]
+    pub exchange_interval: usize,
+    /// Run a baseline optimizer instead of CISAT teams (Annealing, HillClimbing, RandomSearch,
+    /// Evolutionary, or None)
+    #[structopt(long, default_value = "None")]
+    pub baseline: String,
+    /// The kind of each agent on a mixed team (Annealing, HillClimbing, or RandomSearch), reused
+    /// from the start of the list if there are more agents than kinds
+    #[structopt(long, use_delimiter = true)]
+    pub agent_kinds: Vec<String>,
 }
 
+/// This logger prints warnings and errors to standard error, so that they are not dropped
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
 fn main() {
+    // Show warnings from the library
+    log::set_logger(&LOGGER)
+        .map(|()| log::set_max_level(LevelFilter::Warn))
+        .expect("The logger could not be set");
+
     // Parse args
     let args = Cli::from_args();
 
@@ -110,6 +278,150 @@ fn main() {
         ),
     };
 
+    // Match for candidate selection
+    let candidate_selection = match args.candidate_selection.to_lowercase().as_str() {
+        "best" => CandidateSelection::Best,
+        "boltzmann" => CandidateSelection::Boltzmann,
+        &_ => panic!(
+            "{} is not a valid option for --candidate-selection",
+            args.candidate_selection.as_str()
+        ),
+    };
+
+    // Match for step size adaptation
+    let step_size_adaptation = match args.step_size.to_lowercase().as_str() {
+        "onefifthrule" => StepSizeAdaptation::OneFifthRule {
+            window: args.step_size_window,
+            factor: args.step_size_factor,
+        },
+        "acceptancerate" => StepSizeAdaptation::AcceptanceRate {
+            target: args.target_acceptance,
+            window: args.step_size_window,
+            factor: args.step_size_factor,
+        },
+        "none" => StepSizeAdaptation::None,
+        &_ => panic!(
+            "{} is not a valid option for --step-size",
+            args.step_size.as_str()
+        ),
+    };
+
+    // Match for stagnation response
+    let stagnation_response = match args.stagnation.to_lowercase().as_str() {
+        "reheat" => StagnationResponse::Reheat {
+            fraction: args.reheat_fraction,
+        },
+        "restart" => StagnationResponse::Restart,
+        "restartfromteambest" => StagnationResponse::RestartFromTeamBest,
+        "none" => StagnationResponse::None,
+        &_ => panic!(
+            "{} is not a valid option for --stagnation",
+            args.stagnation.as_str()
+        ),
+    };
+
+    // Match for communication style
+    let communication = match args.communication.to_lowercase().as_str() {
+        "constantfrequency" => CommunicationStyle::ConstantFrequency {
+            frequency: args.frequency,
+        },
+        "regularinterval" => CommunicationStyle::RegularInterval {
+            interval: args.interval,
+        },
+        "scheduledmeetings" => CommunicationStyle::ScheduledMeetings {
+            times: args.meeting_times.clone(),
+        },
+        "none" => CommunicationStyle::None,
+        &_ => panic!(
+            "{} is not a valid option for --communication",
+            args.communication.as_str()
+        ),
+    };
+
+    // Match for meeting cost
+    let meeting_cost = match (args.meeting_iterations, args.meeting_evaluations) {
+        (Some(iterations), _) => MeetingCost::Iterations { iterations },
+        (None, Some(evaluations)) => MeetingCost::Evaluations { evaluations },
+        (None, None) => MeetingCost::None,
+    };
+
+    // Match for quality normalization
+    let quality_normalization = match args.normalization.to_lowercase().as_str() {
+        "minshift" => QualityNormalization::MinShift,
+        "rankbased" => QualityNormalization::RankBased,
+        "softmax" => QualityNormalization::Softmax {
+            temperature: args.softmax_temperature,
+        },
+        "none" => QualityNormalization::None,
+        &_ => panic!(
+            "{} is not a valid option for --normalization",
+            args.normalization.as_str()
+        ),
+    };
+
+    // Match for adoption style
+    let adoption = match args.adoption.to_lowercase().as_str() {
+        "weighteddraw" => AdoptionStyle::WeightedDraw,
+        "greedybest" => AdoptionStyle::GreedyBest,
+        "tournament" => AdoptionStyle::Tournament {
+            size: args.tournament_size,
+        },
+        "boltzmann" => AdoptionStyle::Boltzmann {
+            temperature: args.adoption_temperature,
+        },
+        "onlyifbetter" => AdoptionStyle::OnlyIfBetter,
+        "randompeer" => AdoptionStyle::RandomPeer,
+        &_ => panic!(
+            "{} is not a valid option for --adoption",
+            args.adoption.as_str()
+        ),
+    };
+
+    // Match for constraint handling
+    let constraint_handling = match args.constraints.to_lowercase().as_str() {
+        "staticpenalty" => ConstraintHandling::StaticPenalty {
+            penalty: args.penalty,
+        },
+        "adaptivepenalty" => ConstraintHandling::AdaptivePenalty {
+            initial_penalty: args.penalty,
+            decrease_factor: 2.0,
+            increase_factor: 3.0,
+            window: 10,
+        },
+        "feasibilityfirst" => ConstraintHandling::FeasibilityFirst,
+        "stochasticranking" => ConstraintHandling::StochasticRanking {
+            probability: args.ranking_probability,
+        },
+        "none" => ConstraintHandling::None,
+        &_ => panic!(
+            "{} is not a valid option for --constraints",
+            args.constraints.as_str()
+        ),
+    };
+
+    // Match for agent kinds
+    let agent_kinds = args
+        .agent_kinds
+        .iter()
+        .map(|kind| match kind.to_lowercase().as_str() {
+            "annealing" => AgentKind::Annealing,
+            "hillclimbing" => AgentKind::HillClimbing,
+            "randomsearch" => AgentKind::RandomSearch,
+            &_ => panic!("{} is not a valid option for --agent-kinds", kind),
+        })
+        .collect();
+
+    // Match for work pace
+    let work_pace = match (args.work_rates.is_empty(), args.exponential_work) {
+        (true, _) => WorkPace::None,
+        (false, false) => WorkPace::Constant {
+            rates: args.work_rates.clone(),
+        },
+        (false, true) => WorkPace::Exponential {
+            rates: args.work_rates.clone(),
+        },
+    };
+
     // Things
     println!(
         "Solving the {} problem with following parameters",
@@ -122,10 +434,44 @@ fn main() {
         number_of_iterations: args.iter,
         temperature_schedule,
         operational_learning: learning_style,
-        communication: CommunicationStyle::None,
+        number_of_candidates: args.candidates,
+        candidate_selection,
+        mix_move_operators: args.mix_operators,
+        step_size_adaptation,
+        stagnation_response,
+        stagnation_iterations: args.stagnation_iterations,
+        communication,
+        meeting_cost,
         self_bias: args.self_bias,
         quality_bias: args.quality_bias,
+        quality_normalization,
+        adoption,
+        crossover_rate: args.crossover_rate,
         satisficing_fraction: args.satisficing,
+        constraint_handling,
+        evaluation_budget: args.budget,
+        seed: args.seed,
+        parallel_agents: args.parallel_agents,
+        parallel_teams: args.parallel_teams,
+        number_of_threads: args.threads,
+        work_pace,
+        temperature_ladder: TemperatureLadder::Geometric {
+            lowest: args.lowest_temperature,
+            highest: args.highest_temperature,
+        },
+        exchange_interval: args.exchange_interval,
+        agent_kinds,
+    };
+
+    // Start from previously learned matrices
+    let params = match &args.import_learning {
+        Some(file_name) => {
+            match LearnedMatrix::load_all(file_name).and_then(|x| LearnedMatrix::average(&x)) {
+                Ok(matrix) => params.with_learned_matrix(matrix),
+                Err(error) => panic!("Could not load {}: {}", file_name, error),
+            }
+        }
+        None => params,
     };
 
     println!("{}", params);
@@ -134,12 +480,61 @@ fn main() {
 
     match args.problem.to_lowercase().as_str() {
         "ackley" => {
-            let cisat = Cohort::<Ackley<5>>::new(params);
-            run_all(cisat, args);
+            run::<Ackley<5>>(params, args);
+        }
+        "sphere" => {
+            run::<Sphere<5>>(params, args);
+        }
+        "rastrigin" => {
+            run::<Rastrigin<5>>(params, args);
+        }
+        "rosenbrock" => {
+            run::<Rosenbrock<5>>(params, args);
+        }
+        "schwefel" => {
+            run::<Schwefel<5>>(params, args);
+        }
+        "griewank" => {
+            run::<Griewank<5>>(params, args);
+        }
+        "levy" => {
+            run::<Levy<5>>(params, args);
+        }
+        "michalewicz" => {
+            run::<Michalewicz<5>>(params, args);
+        }
+        "styblinskitang" => {
+            run::<StyblinskiTang<5>>(params, args);
+        }
+        "tsp" => {
+            if let Some(file_name) = &args.instance {
+                match TspInstance::from_tsplib_file(file_name) {
+                    Ok(instance) => Tsp::set_instance(instance),
+                    Err(error) => panic!("Could not load {}: {}", file_name, error),
+                }
+            }
+            run::<Tsp>(params, args);
+        }
+        "knapsack" => {
+            if let Some(file_name) = &args.instance {
+                match KnapsackInstance::from_or_library_file(file_name, args.problem_index) {
+                    Ok(instance) => Knapsack::set_instance(instance),
+                    Err(error) => panic!("Could not load {}: {}", file_name, error),
+                }
+            }
+            run::<Knapsack>(params, args);
+        }
+        "setcover" => {
+            if let Some(file_name) = &args.instance {
+                match SetCoverInstance::from_or_library_file(file_name) {
+                    Ok(instance) => SetCover::set_instance(instance),
+                    Err(error) => panic!("Could not load {}: {}", file_name, error),
+                }
+            }
+            run::<SetCover>(params, args);
         }
         "structure" => {
-            let cisat = Cohort::<Structure>::new(params);
-            run_all(cisat, args);
+            run::<Structure>(params, args);
         }
         &_ => panic!(
             "{} is not a valid option for --problem",
@@ -148,16 +543,66 @@ fn main() {
     }
 }
 
+fn run<S: Solution + 'static>(params: Parameters, args: Cli) {
+    match args.baseline.to_lowercase().as_str() {
+        "annealing" => {
+            let cisat = Cohort::<S, Agent<S>, SoloTeam<S, Agent<S>>>::new(params);
+            run_all(cisat, args);
+        }
+        "hillclimbing" => {
+            let cisat = Cohort::<S, HillClimber<S>, Team<S, HillClimber<S>>>::new(params);
+            run_all(cisat, args);
+        }
+        "randomsearch" => {
+            let cisat = Cohort::<S, RandomSearch<S>, Team<S, RandomSearch<S>>>::new(params);
+            run_all(cisat, args);
+        }
+        "evolutionary" => {
+            let cisat = Cohort::<S, Agent<S>, EvolutionaryTeam<S>>::new(params);
+            run_all(cisat, args);
+        }
+        "none" if args.agent_kinds.is_empty() => run_teams::<S, Agent<S>>(params, args),
+        "none" => run_teams::<S, Box<dyn AgentMethods<S>>>(params, args),
+        &_ => panic!(
+            "{} is not a valid option for --baseline",
+            args.baseline.as_str()
+        ),
+    }
+}
+
+fn run_teams<S: Solution, A: AgentMethods<S>>(params: Parameters, args: Cli) {
+    if args.event_driven {
+        let cisat = Cohort::<S, A, EventTeam<S, A>>::new(params);
+        run_all(cisat, args);
+    } else if args.replica_exchange {
+        let cisat = Cohort::<S, A, ReplicaTeam<S, A>>::new(params);
+        run_all(cisat, args);
+    } else {
+        let cisat = Cohort::<S, A, Team<S, A>>::new(params);
+        run_all(cisat, args);
+    }
+}
+
 fn run_all<S: Solution, A: AgentMethods<S>, T: TeamMethods<S, A>>(
     mut cisat: Cohort<S, A, T>,
     args: Cli,
 ) {
     let started = Instant::now();
     if args.parallel {
-        let bar = ProgressBar::new_spinner();
-        bar.set_style(ProgressStyle::default_bar().template("{spinner} {elapsed_precise} elapsed"));
-        bar.enable_steady_tick(100);
-        cisat.solve();
+        let bar = ProgressBar::new((args.teams * args.iter) as u64);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template("[{msg}] {wide_bar} [{prefix} teams, {percent}%, ~{eta} remaining]"),
+        );
+        bar.set_message("Starting...");
+        bar.set_prefix(format!("0/{}", args.teams).as_str());
+        cisat.solve_with_progress(|progress| {
+            bar.set_position(progress.iterations_completed as u64);
+            bar.set_prefix(
+                format!("{}/{}", progress.teams_completed, progress.number_of_teams).as_str(),
+            );
+            bar.set_message(format!("Best: {:.2}", progress.best_quality_so_far).as_str());
+        });
         bar.finish_and_clear();
     } else {
         let bar = ProgressBar::new(args.iter as u64);
@@ -178,4 +623,12 @@ fn run_all<S: Solution, A: AgentMethods<S>, T: TeamMethods<S, A>>(
         HumanDuration(started.elapsed()),
         cisat.get_best_solution_so_far()
     );
+    println!("{}", cisat.get_counters());
+    if let Some(file_name) = &args.export_learning {
+        if let Err(error) =
+            LearnedMatrix::save_all(&cisat.get_learned_matrices().concat(), file_name)
+        {
+            panic!("Could not save {}: {}", file_name, error);
+        }
+    }
 }
@@ -1,51 +1,173 @@
 //! This is an example problem for optimizing the Ackley function
 
-use super::super::{utilities::randomness::random_uniform_vector, utilities::Solution};
-use std::{cmp::Ordering, ops::Sub};
+use super::{
+    super::{
+        utilities::randomness::{
+            random_gaussian_vector, random_index, random_uniform_vector, random_unit_draw,
+        },
+        utilities::Solution,
+    },
+    Benchmark, BoundHandling, Clip,
+};
+use crate::CisatSolution;
+use std::marker::PhantomData;
 
-#[derive(Clone, Debug)]
-/// This contains solutions for the Ackley problem
-pub struct Ackley<const NUMBER_OF_DIMENSIONS: usize> {
+#[derive(Clone, Debug, CisatSolution)]
+/// This contains solutions for the Ackley problem. The bound-handling policy `B` decides what
+/// happens to coordinates that a move pushes outside of the bounds, and defaults to `Clip`.
+///
+/// The move operators are:
+/// - 0: Cauchy step on all coordinates
+/// - 1: Cauchy step on a single coordinate
+/// - 2: Gaussian step on all coordinates
+/// - 3: Swap two coordinates
+/// - 4: Reset a single coordinate to a random value
+///
+/// Solutions recombine by uniform crossover, taking each coordinate from either parent with equal
+/// probability.
+pub struct Ackley<const NUMBER_OF_DIMENSIONS: usize, B: BoundHandling = Clip> {
     /// This contains direct objective function values
     objective_function_value: Vec<f64>,
     /// This contains a single quality scalar derived from objective function values
+    #[quality]
     quality_scalar: f64,
     /// This contains the parameters
     x: Vec<f64>,
+    /// Bookkeeping the bound-handling policy
+    bound_handling: PhantomData<B>,
 }
 
-impl<const NUMBER_OF_DIMENSIONS: usize> Solution for Ackley<{ NUMBER_OF_DIMENSIONS }> {
-    const NUMBER_OF_MOVE_OPERATORS: usize = 1;
+impl<const NUMBER_OF_DIMENSIONS: usize, B: BoundHandling> Solution
+    for Ackley<{ NUMBER_OF_DIMENSIONS }, B>
+{
+    const NUMBER_OF_MOVE_OPERATORS: usize = 5;
     const NUMBER_OF_OBJECTIVES: usize = 1;
+    const STEP_SIZE_SCALES_TEMPERATURE: bool = true;
 
-    fn new() -> Ackley<{ NUMBER_OF_DIMENSIONS }> {
+    fn new() -> Ackley<{ NUMBER_OF_DIMENSIONS }, B> {
         let mut solution = Ackley {
             objective_function_value: vec![0.0; 1],
-            x: random_uniform_vector(NUMBER_OF_DIMENSIONS, -10.0, 10.0),
+            x: random_uniform_vector(NUMBER_OF_DIMENSIONS, Self::LOWER_BOUND, Self::UPPER_BOUND),
             quality_scalar: 0.0,
+            bound_handling: PhantomData,
         };
         solution.evaluate();
         solution
     }
 
-    fn apply_move_operator(&mut self, _move_index: usize, temperature: f64) {
+    fn apply_move_operator(&mut self, move_index: usize, temperature: f64) {
+        match move_index {
+            0 => self.cauchy_step_all(temperature),
+            1 => self.cauchy_step_single(temperature),
+            2 => self.gaussian_step_all(temperature),
+            3 => self.swap_coordinates(),
+            4 => self.reset_coordinate(),
+            _ => panic!(
+                "The move index {} is not in the valid range [0, {})",
+                move_index,
+                Self::NUMBER_OF_MOVE_OPERATORS
+            ),
+        }
+        self.enforce_bounds();
+        self.evaluate();
+    }
+
+    fn get_quality_scalar(&self) -> f64 {
+        self.quality_scalar
+    }
+
+    fn crossover(&self, other: &Self) -> Option<Self> {
+        let mut child = self.clone();
+        for (xi, yi) in child.x.iter_mut().zip(other.x.iter()) {
+            if random_unit_draw() < 0.5 {
+                *xi = *yi;
+            }
+        }
+        child.evaluate();
+        Some(child)
+    }
+}
+
+impl<const NUMBER_OF_DIMENSIONS: usize, B: BoundHandling> Benchmark
+    for Ackley<{ NUMBER_OF_DIMENSIONS }, B>
+{
+    const LOWER_BOUND: f64 = -10.0;
+    const UPPER_BOUND: f64 = 10.0;
+
+    fn optimal_objective_value() -> Option<f64> {
+        Some(0.0)
+    }
+
+    fn get_objective_value(&self) -> f64 {
+        self.objective_function_value[0]
+    }
+}
+
+impl<const NUMBER_OF_DIMENSIONS: usize, B: BoundHandling> Ackley<{ NUMBER_OF_DIMENSIONS }, B> {
+    /// This makes a solution at a specific point
+    pub fn from_point(x: Vec<f64>) -> Self {
+        assert_eq!(
+            x.len(),
+            NUMBER_OF_DIMENSIONS,
+            "The point must have {} dimensions",
+            NUMBER_OF_DIMENSIONS
+        );
+        let mut solution = Ackley {
+            objective_function_value: vec![0.0; 1],
+            x,
+            quality_scalar: 0.0,
+            bound_handling: PhantomData,
+        };
+        solution.evaluate();
+        solution
+    }
+
+    /// Takes a Cauchy-distributed step on every coordinate
+    fn cauchy_step_all(&mut self, temperature: f64) {
         let perturbation_arg = random_uniform_vector(
             self.x.len(),
             -std::f64::consts::PI / 2.0,
             std::f64::consts::PI / 2.0,
         );
-        for i in 0..self.x.len() {
-            self.x[i] += perturbation_arg[i].tan() * temperature;
+        for (xi, arg) in self.x.iter_mut().zip(perturbation_arg) {
+            *xi += arg.tan() * temperature;
         }
-        self.evaluate();
     }
 
-    fn get_quality_scalar(&self) -> f64 {
-        self.quality_scalar
+    /// Takes a Cauchy-distributed step on a single random coordinate
+    fn cauchy_step_single(&mut self, temperature: f64) {
+        let arg = std::f64::consts::PI * (random_unit_draw() - 0.5);
+        let idx = random_index(self.x.len());
+        self.x[idx] += arg.tan() * temperature;
+    }
+
+    /// Takes a Gaussian step on every coordinate
+    fn gaussian_step_all(&mut self, temperature: f64) {
+        let perturbation = random_gaussian_vector(self.x.len(), 0.0, temperature);
+        for (xi, step) in self.x.iter_mut().zip(perturbation) {
+            *xi += step;
+        }
+    }
+
+    /// Swaps the values of two random coordinates
+    fn swap_coordinates(&mut self) {
+        let n = self.x.len();
+        self.x.swap(random_index(n), random_index(n));
+    }
+
+    /// Resets a single random coordinate to a uniformly random value within the bounds
+    fn reset_coordinate(&mut self) {
+        let idx = random_index(self.x.len());
+        self.x[idx] = random_uniform_vector(1, Self::LOWER_BOUND, Self::UPPER_BOUND)[0];
+    }
+
+    /// Brings every coordinate back within the bounds using the bound-handling policy
+    fn enforce_bounds(&mut self) {
+        for xi in self.x.iter_mut() {
+            *xi = B::apply(*xi, Self::LOWER_BOUND, Self::UPPER_BOUND);
+        }
     }
-}
 
-impl<const NUMBER_OF_DIMENSIONS: usize> Ackley<{ NUMBER_OF_DIMENSIONS }> {
     /// This function offers some functionality for evaluation
     fn evaluate(&mut self) {
         let n = self.x.len();
@@ -63,31 +185,3 @@ impl<const NUMBER_OF_DIMENSIONS: usize> Ackley<{ NUMBER_OF_DIMENSIONS }> {
         self.quality_scalar = 20.0 + std::f64::consts::E - fx;
     }
 }
-
-impl<const NUMBER_OF_DIMENSIONS: usize> PartialEq for Ackley<{ NUMBER_OF_DIMENSIONS }> {
-    fn eq(&self, other: &Self) -> bool {
-        self.quality_scalar == other.quality_scalar
-    }
-}
-
-impl<const NUMBER_OF_DIMENSIONS: usize> Eq for Ackley<{ NUMBER_OF_DIMENSIONS }> {}
-
-impl<const NUMBER_OF_DIMENSIONS: usize> PartialOrd for Ackley<{ NUMBER_OF_DIMENSIONS }> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.quality_scalar.partial_cmp(&other.quality_scalar)
-    }
-}
-
-impl<const NUMBER_OF_DIMENSIONS: usize> Ord for Ackley<{ NUMBER_OF_DIMENSIONS }> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-impl<const NUMBER_OF_DIMENSIONS: usize> Sub for Ackley<{ NUMBER_OF_DIMENSIONS }> {
-    type Output = f64;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.quality_scalar - rhs.quality_scalar
-    }
-}
@@ -0,0 +1,20 @@
+//! This module contains the Benchmark trait, which is implemented by the continuous test functions
+
+use super::super::utilities::Solution;
+
+/// This trait is implemented by continuous test functions with known bounds and a known global
+/// minimum, so that results can be reported as a distance from the optimum
+pub trait Benchmark: Solution {
+    /// The lower bound of every dimension
+    const LOWER_BOUND: f64;
+    /// The upper bound of every dimension
+    const UPPER_BOUND: f64;
+    /// The objective function value at the global minimum, if it is known for this dimension
+    fn optimal_objective_value() -> Option<f64>;
+    /// The objective function value of the solution
+    fn get_objective_value(&self) -> f64;
+    /// The distance between the objective function value of the solution and the global minimum
+    fn distance_from_optimum(&self) -> Option<f64> {
+        Self::optimal_objective_value().map(|optimum| self.get_objective_value() - optimum)
+    }
+}
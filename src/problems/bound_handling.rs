@@ -0,0 +1,73 @@
+//! This module contains policies for bringing variables that leave their bounds back inside
+
+use std::fmt::Debug;
+
+/// This trait is implemented by bound-handling policies, which are chosen through a type parameter
+/// on the problems that support them:
+/// ```
+/// use cisat::{Parameters, Cohort, problems::{Ackley, Reflect}};
+/// let mut x = Cohort::<Ackley<5, Reflect>>::new(Parameters::default());
+/// x.solve();
+/// ```
+pub trait BoundHandling: Clone + Debug + Send + Sync {
+    /// Maps a value onto the interval between `lower` and `upper`
+    fn apply(value: f64, lower: f64, upper: f64) -> f64;
+}
+
+/// Values outside the bounds are moved to the nearest bound
+#[derive(Clone, Debug)]
+pub struct Clip;
+
+impl BoundHandling for Clip {
+    fn apply(value: f64, lower: f64, upper: f64) -> f64 {
+        value.clamp(lower, upper)
+    }
+}
+
+/// Values outside the bounds are mirrored back inside, as if the bounds were walls
+#[derive(Clone, Debug)]
+pub struct Reflect;
+
+impl BoundHandling for Reflect {
+    fn apply(value: f64, lower: f64, upper: f64) -> f64 {
+        // Reflecting repeatedly is the same as folding onto an interval of twice the width
+        let width = upper - lower;
+        let folded = (value - lower).rem_euclid(2.0 * width);
+        if folded > width {
+            upper - (folded - width)
+        } else {
+            lower + folded
+        }
+    }
+}
+
+/// Values outside the bounds re-enter from the opposite bound, as if the space were periodic
+#[derive(Clone, Debug)]
+pub struct Wrap;
+
+impl BoundHandling for Wrap {
+    fn apply(value: f64, lower: f64, upper: f64) -> f64 {
+        lower + (value - lower).rem_euclid(upper - lower)
+    }
+}
+
+#[cfg(test)]
+mod bound_handling_tests {
+    use crate::problems::{BoundHandling, Clip, Reflect, Wrap};
+
+    #[test]
+    fn test_values_inside_bounds_are_unchanged() {
+        assert_eq!(Clip::apply(0.5, -1.0, 1.0), 0.5);
+        assert_eq!(Reflect::apply(0.5, -1.0, 1.0), 0.5);
+        assert_eq!(Wrap::apply(0.5, -1.0, 1.0), 0.5);
+    }
+
+    #[test]
+    fn test_values_outside_bounds() {
+        assert_eq!(Clip::apply(1.5, -1.0, 1.0), 1.0);
+        assert_eq!(Reflect::apply(1.5, -1.0, 1.0), 0.5);
+        assert_eq!(Reflect::apply(-3.5, -1.0, 1.0), 0.5);
+        assert_eq!(Wrap::apply(1.5, -1.0, 1.0), -0.5);
+        assert_eq!(Wrap::apply(-1.5, -1.0, 1.0), 0.5);
+    }
+}
@@ -0,0 +1,139 @@
+//! This module contains the ContinuousBenchmark type, which turns a test function into a problem
+
+use super::{
+    super::{utilities::randomness::random_uniform_vector, utilities::Solution},
+    Benchmark,
+};
+use crate::CisatSolution;
+use std::{fmt::Debug, marker::PhantomData};
+
+/// This trait is implemented by continuous test functions, which are minimized within the same
+/// bounds on every dimension. New test functions can be solved by plugging them into
+/// `ContinuousBenchmark`:
+/// ```
+/// use cisat::{Cohort, Parameters, problems::{ContinuousBenchmark, TestFunction}};
+///
+/// /// The sum of absolute values
+/// #[derive(Clone, Debug)]
+/// struct Manhattan;
+///
+/// impl TestFunction for Manhattan {
+///     const LOWER_BOUND: f64 = -1.0;
+///     const UPPER_BOUND: f64 = 1.0;
+///
+///     fn evaluate(x: &[f64]) -> f64 {
+///         x.iter().map(|xi| xi.abs()).sum()
+///     }
+///
+///     fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+///         Some(0.0)
+///     }
+/// }
+///
+/// let mut x = Cohort::<ContinuousBenchmark<5, Manhattan>>::new(Parameters::default());
+/// x.solve();
+/// ```
+pub trait TestFunction: Clone + Debug + Send + Sync {
+    /// The lower bound of every dimension
+    const LOWER_BOUND: f64;
+    /// The upper bound of every dimension
+    const UPPER_BOUND: f64;
+    /// The objective function value at a point
+    fn evaluate(x: &[f64]) -> f64;
+    /// The objective function value at the global minimum, if it is known for this dimension
+    fn optimal_objective_value(number_of_dimensions: usize) -> Option<f64>;
+}
+
+#[derive(Clone, Debug, CisatSolution)]
+/// This contains solutions for a continuous test function `F`. The single move operator takes a
+/// Cauchy-distributed step on every coordinate, scaled to the width of the search space and kept
+/// within the bounds.
+pub struct ContinuousBenchmark<const NUMBER_OF_DIMENSIONS: usize, F: TestFunction> {
+    /// This contains direct objective function values
+    objective_function_value: Vec<f64>,
+    /// This contains a single quality scalar derived from objective function values
+    #[quality]
+    quality_scalar: f64,
+    /// This contains the parameters
+    x: Vec<f64>,
+    /// Bookkeeping the test function
+    test_function: PhantomData<F>,
+}
+
+impl<const NUMBER_OF_DIMENSIONS: usize, F: TestFunction> Solution
+    for ContinuousBenchmark<{ NUMBER_OF_DIMENSIONS }, F>
+{
+    const NUMBER_OF_MOVE_OPERATORS: usize = 1;
+    const NUMBER_OF_OBJECTIVES: usize = 1;
+    const STEP_SIZE_SCALES_TEMPERATURE: bool = true;
+
+    fn new() -> ContinuousBenchmark<{ NUMBER_OF_DIMENSIONS }, F> {
+        ContinuousBenchmark::from_point(random_uniform_vector(
+            NUMBER_OF_DIMENSIONS,
+            F::LOWER_BOUND,
+            F::UPPER_BOUND,
+        ))
+    }
+
+    fn apply_move_operator(&mut self, _move_index: usize, temperature: f64) {
+        // Steps are scaled to the width of the search space, and kept within the bounds
+        let scale = (F::UPPER_BOUND - F::LOWER_BOUND) / 20.0;
+        let perturbation_arg = random_uniform_vector(
+            self.x.len(),
+            -std::f64::consts::PI / 2.0,
+            std::f64::consts::PI / 2.0,
+        );
+        for (xi, arg) in self.x.iter_mut().zip(perturbation_arg) {
+            *xi = (*xi + arg.tan() * temperature * scale).clamp(F::LOWER_BOUND, F::UPPER_BOUND);
+        }
+        self.evaluate();
+    }
+
+    fn get_quality_scalar(&self) -> f64 {
+        self.quality_scalar
+    }
+}
+
+impl<const NUMBER_OF_DIMENSIONS: usize, F: TestFunction> Benchmark
+    for ContinuousBenchmark<{ NUMBER_OF_DIMENSIONS }, F>
+{
+    const LOWER_BOUND: f64 = F::LOWER_BOUND;
+    const UPPER_BOUND: f64 = F::UPPER_BOUND;
+
+    fn optimal_objective_value() -> Option<f64> {
+        F::optimal_objective_value(NUMBER_OF_DIMENSIONS)
+    }
+
+    fn get_objective_value(&self) -> f64 {
+        self.objective_function_value[0]
+    }
+}
+
+impl<const NUMBER_OF_DIMENSIONS: usize, F: TestFunction>
+    ContinuousBenchmark<{ NUMBER_OF_DIMENSIONS }, F>
+{
+    /// This makes a solution at a specific point
+    pub fn from_point(x: Vec<f64>) -> Self {
+        assert_eq!(
+            x.len(),
+            NUMBER_OF_DIMENSIONS,
+            "The point must have {} dimensions",
+            NUMBER_OF_DIMENSIONS
+        );
+        let mut solution = ContinuousBenchmark {
+            objective_function_value: vec![0.0; 1],
+            x,
+            quality_scalar: 0.0,
+            test_function: PhantomData,
+        };
+        solution.evaluate();
+        solution
+    }
+
+    /// This function offers some functionality for evaluation
+    fn evaluate(&mut self) {
+        let fx = F::evaluate(&self.x);
+        self.objective_function_value = vec![fx; 1];
+        self.quality_scalar = -fx;
+    }
+}
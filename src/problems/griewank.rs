@@ -0,0 +1,30 @@
+//! This is an example problem for optimizing the Griewank function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Griewank problem
+pub type Griewank<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, GriewankFunction>;
+
+/// This is the Griewank function, a sphere with a product of cosines added
+#[derive(Clone, Debug)]
+pub struct GriewankFunction;
+
+impl TestFunction for GriewankFunction {
+    const LOWER_BOUND: f64 = -600.0;
+    const UPPER_BOUND: f64 = 600.0;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        let mut square_sum = 0.0;
+        let mut cosine_product = 1.0;
+        for (i, xi) in x.iter().enumerate() {
+            square_sum += xi.powi(2);
+            cosine_product *= (xi / ((i + 1) as f64).sqrt()).cos();
+        }
+        square_sum / 4000.0 - cosine_product + 1.0
+    }
+
+    fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+        Some(0.0)
+    }
+}
@@ -0,0 +1,432 @@
+//! This is an example problem for the binary (multidimensional) knapsack problem
+//!
+//! Because `Solution::new` takes no arguments, every `Knapsack` solution is built on the instance
+//! that was most recently passed to `Knapsack::set_instance`. If no instance has been set, a random
+//! instance with `DEFAULT_NUMBER_OF_ITEMS` items is generated the first time one is needed.
+
+use super::{
+    super::utilities::{
+        parsing::{invalid_data, NumberReader},
+        randomness::{random_index, random_unit_draw, seeded_rng},
+        Solution,
+    },
+    shared_instance::SharedInstance,
+};
+use crate::CisatSolution;
+use rand_distr::{Distribution, Uniform};
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+/// Number of items in the instance used when no other instance has been set
+const DEFAULT_NUMBER_OF_ITEMS: usize = 50;
+
+/// The instance that new solutions are built on
+static INSTANCE: SharedInstance<KnapsackInstance> = SharedInstance::new();
+
+/// This contains a knapsack instance: items with values and weights, and one capacity for each
+/// weight constraint
+#[derive(Clone)]
+pub struct KnapsackInstance {
+    /// The value of each item
+    values: Vec<f64>,
+    /// The weight of each item in each constraint, indexed by constraint and then by item
+    weights: Vec<Vec<f64>>,
+    /// The capacity of each constraint
+    capacities: Vec<f64>,
+}
+
+impl KnapsackInstance {
+    /// Makes an instance from values, weights (indexed by constraint and then by item) and
+    /// capacities
+    pub fn new(values: Vec<f64>, weights: Vec<Vec<f64>>, capacities: Vec<f64>) -> Self {
+        assert_eq!(
+            weights.len(),
+            capacities.len(),
+            "There must be one row of weights for each capacity"
+        );
+        assert!(
+            weights.iter().all(|row| row.len() == values.len()),
+            "There must be one weight for each item in every row"
+        );
+        KnapsackInstance {
+            values,
+            weights,
+            capacities,
+        }
+    }
+
+    /// Generates a random instance in the style of Chu and Beasley: weights are uniform between 1
+    /// and 1000, each capacity is `tightness` times the total weight of its constraint, and
+    /// values are correlated with weights
+    /// ```
+    /// use cisat::problems::KnapsackInstance;
+    /// let instance = KnapsackInstance::random(100, 5, 0.5, 0);
+    /// assert_eq!(instance.get_number_of_items(), 100);
+    /// ```
+    pub fn random(
+        number_of_items: usize,
+        number_of_constraints: usize,
+        tightness: f64,
+        seed: u64,
+    ) -> Self {
+        let mut rng = seeded_rng(Some(seed));
+        let weight_distribution = Uniform::new_inclusive(1, 1000);
+        let weights: Vec<Vec<f64>> = (0..number_of_constraints)
+            .map(|_| {
+                (0..number_of_items)
+                    .map(|_| weight_distribution.sample(&mut rng) as f64)
+                    .collect()
+            })
+            .collect();
+        let capacities = weights
+            .iter()
+            .map(|row| (tightness * row.iter().sum::<f64>()).floor())
+            .collect();
+        let noise_distribution = Uniform::new_inclusive(0, 500);
+        let values = (0..number_of_items)
+            .map(|item| {
+                let total_weight: f64 = weights.iter().map(|row| row[item]).sum();
+                (total_weight / number_of_constraints as f64
+                    + noise_distribution.sample(&mut rng) as f64)
+                    .floor()
+            })
+            .collect();
+        KnapsackInstance::new(values, weights, capacities)
+    }
+
+    /// Loads one instance from a file in the OR-Library multidimensional knapsack format (as used
+    /// by `mknap1` and `mknapcb1` through `mknapcb9`). Such files hold several instances, so
+    /// `problem_index` picks which one to load, counting from zero.
+    pub fn from_or_library_file<P: AsRef<Path>>(path: P, problem_index: usize) -> io::Result<Self> {
+        KnapsackInstance::from_or_library_str(&fs::read_to_string(path)?, problem_index)
+    }
+
+    /// Parses one instance from the contents of an OR-Library multidimensional knapsack file
+    /// ```
+    /// use cisat::problems::KnapsackInstance;
+    /// let contents = "1
+    /// 3 1 0
+    /// 10 20 30
+    /// 1 2 3
+    /// 4";
+    /// let instance = KnapsackInstance::from_or_library_str(contents, 0).unwrap();
+    /// assert_eq!(instance.get_number_of_items(), 3);
+    /// ```
+    pub fn from_or_library_str(contents: &str, problem_index: usize) -> io::Result<Self> {
+        let mut reader = NumberReader::new(contents);
+        let number_of_problems: usize = reader.next()?;
+        if problem_index >= number_of_problems {
+            return Err(invalid_data(format!(
+                "Asked for problem {} but the file only holds {}",
+                problem_index, number_of_problems
+            )));
+        }
+        for index in 0..=problem_index {
+            let number_of_items: usize = reader.next()?;
+            let number_of_constraints: usize = reader.next()?;
+            let _optimal_value: f64 = reader.next()?;
+            let values = reader.next_n(number_of_items)?;
+            let weights = (0..number_of_constraints)
+                .map(|_| reader.next_n(number_of_items))
+                .collect::<io::Result<Vec<Vec<f64>>>>()?;
+            let capacities = reader.next_n(number_of_constraints)?;
+            if index == problem_index {
+                return Ok(KnapsackInstance::new(values, weights, capacities));
+            }
+        }
+        unreachable!()
+    }
+
+    /// The number of items in the instance
+    pub fn get_number_of_items(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The number of weight constraints in the instance
+    pub fn get_number_of_constraints(&self) -> usize {
+        self.capacities.len()
+    }
+}
+
+impl fmt::Debug for KnapsackInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KnapsackInstance")
+            .field("number_of_items", &self.get_number_of_items())
+            .field("number_of_constraints", &self.get_number_of_constraints())
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, CisatSolution)]
+/// This contains solutions for the knapsack problem, as a choice of which items to pack.
+///
+/// Feasible solutions have the total value of the packed items as their quality. Infeasible
+/// solutions have the negative of their total overload, relative to each capacity, so that every
+/// feasible solution is better than every infeasible one. The overload is also reported as the
+/// constraint violation.
+///
+/// The move operators are:
+/// - 0: Bit-flip, which packs or unpacks a single item
+/// - 1: Swap, which unpacks one item and packs another
+/// - 2: Repair, which unpacks the least efficient items until the solution is feasible and then
+///   packs the most efficient items that still fit
+///
+/// Solutions are built on the current instance:
+/// ```
+/// use cisat::{Parameters, Cohort, problems::{Knapsack, KnapsackInstance}};
+/// Knapsack::set_instance(KnapsackInstance::random(30, 2, 0.5, 0));
+/// let mut x = Cohort::<Knapsack>::new(Parameters::default());
+/// x.solve();
+/// ```
+pub struct Knapsack {
+    /// This contains the instance that the solution is for
+    instance: Arc<KnapsackInstance>,
+    /// This contains whether each item is packed
+    packed: Vec<bool>,
+    /// This contains direct objective function values
+    objective_function_value: Vec<f64>,
+    /// This contains a single quality scalar derived from objective function values
+    #[quality]
+    quality_scalar: f64,
+    /// This contains the total overload of the packed items
+    constraint_violation: f64,
+}
+
+impl Solution for Knapsack {
+    const NUMBER_OF_MOVE_OPERATORS: usize = 3;
+    const NUMBER_OF_OBJECTIVES: usize = 1;
+
+    fn new() -> Knapsack {
+        // Start from a random selection, repaired to feasibility
+        let instance = Knapsack::get_instance();
+        let mut solution = Knapsack {
+            packed: (0..instance.get_number_of_items())
+                .map(|_| random_unit_draw() < 0.5)
+                .collect(),
+            instance,
+            objective_function_value: vec![0.0; 1],
+            quality_scalar: 0.0,
+            constraint_violation: 0.0,
+        };
+        solution.repair();
+        solution.evaluate();
+        solution
+    }
+
+    fn apply_move_operator(&mut self, move_index: usize, _temperature: f64) {
+        match move_index {
+            0 => self.bit_flip(),
+            1 => self.swap(),
+            2 => self.repair(),
+            _ => panic!(
+                "The move index {} is not in the valid range [0, {})",
+                move_index,
+                Knapsack::NUMBER_OF_MOVE_OPERATORS
+            ),
+        }
+        self.evaluate();
+    }
+
+    fn get_quality_scalar(&self) -> f64 {
+        self.quality_scalar
+    }
+
+    fn get_constraint_violation(&self) -> f64 {
+        self.constraint_violation
+    }
+}
+
+impl Knapsack {
+    /// Sets the instance that new solutions are built on, across the whole process. Solutions that
+    /// already exist keep the instance they were built on.
+    pub fn set_instance(instance: KnapsackInstance) {
+        INSTANCE.set(instance);
+    }
+
+    /// Gets the instance that new solutions are built on, generating the default instance if none
+    /// has been set
+    pub fn get_instance() -> Arc<KnapsackInstance> {
+        INSTANCE.get_or_insert_with(|| KnapsackInstance::random(DEFAULT_NUMBER_OF_ITEMS, 1, 0.5, 0))
+    }
+
+    /// Whether each item is packed
+    pub fn get_packed(&self) -> &[bool] {
+        &self.packed
+    }
+
+    /// The total value of the packed items
+    pub fn get_total_value(&self) -> f64 {
+        self.objective_function_value[0]
+    }
+
+    /// Whether the packed items fit within every capacity
+    pub fn is_feasible(&self) -> bool {
+        self.get_overload() == 0.0
+    }
+
+    /// The total amount by which the packed items exceed each capacity, relative to that capacity
+    fn get_overload(&self) -> f64 {
+        self.get_loads()
+            .iter()
+            .zip(self.instance.capacities.iter())
+            .map(|(load, capacity)| ((load - capacity) / relative_scale(*capacity)).max(0.0))
+            .sum()
+    }
+
+    /// The total weight of the packed items in each constraint
+    fn get_loads(&self) -> Vec<f64> {
+        self.instance
+            .weights
+            .iter()
+            .map(|row| self.packed_items().map(|item| row[item]).sum())
+            .collect()
+    }
+
+    /// Iterates over the indices of the packed items
+    fn packed_items(&self) -> impl Iterator<Item = usize> + '_ {
+        self.packed
+            .iter()
+            .enumerate()
+            .filter(|(_, packed)| **packed)
+            .map(|(item, _)| item)
+    }
+
+    /// The value of an item per unit of weight, with weights taken relative to each capacity
+    fn efficiency(&self, item: usize) -> f64 {
+        let relative_weight: f64 = self
+            .instance
+            .weights
+            .iter()
+            .zip(self.instance.capacities.iter())
+            .map(|(row, capacity)| row[item] / relative_scale(*capacity))
+            .sum();
+        self.instance.values[item] / relative_weight
+    }
+
+    /// Packs or unpacks a single random item
+    fn bit_flip(&mut self) {
+        let item = random_index(self.packed.len());
+        self.packed[item] = !self.packed[item];
+    }
+
+    /// Unpacks a random packed item and packs a random unpacked item
+    fn swap(&mut self) {
+        let packed: Vec<usize> = self.packed_items().collect();
+        let unpacked: Vec<usize> = (0..self.packed.len())
+            .filter(|item| !self.packed[*item])
+            .collect();
+        if packed.is_empty() || unpacked.is_empty() {
+            self.bit_flip();
+            return;
+        }
+        self.packed[packed[random_index(packed.len())]] = false;
+        self.packed[unpacked[random_index(unpacked.len())]] = true;
+    }
+
+    /// Unpacks the least efficient items until the solution is feasible, then packs the most
+    /// efficient items that still fit
+    fn repair(&mut self) {
+        let instance = self.instance.clone();
+        let efficiencies: Vec<f64> = (0..self.packed.len())
+            .map(|item| self.efficiency(item))
+            .collect();
+        let mut items: Vec<usize> = (0..self.packed.len()).collect();
+        items.sort_by(|a, b| efficiencies[*b].total_cmp(&efficiencies[*a]));
+
+        // Keep a running total of the load in each constraint, rather than summing it per item
+        let mut loads = self.get_loads();
+        let fits = |loads: &[f64]| {
+            loads
+                .iter()
+                .zip(instance.capacities.iter())
+                .all(|(load, capacity)| load <= capacity)
+        };
+        let add = |loads: &mut [f64], item: usize, sign: f64| {
+            for (load, row) in loads.iter_mut().zip(instance.weights.iter()) {
+                *load += sign * row[item];
+            }
+        };
+
+        // Drop items, starting with the least efficient
+        for item in items.iter().rev() {
+            if fits(&loads) {
+                break;
+            }
+            if self.packed[*item] {
+                self.packed[*item] = false;
+                add(&mut loads, *item, -1.0);
+            }
+        }
+
+        // Add items, starting with the most efficient
+        for item in items.iter() {
+            if !self.packed[*item] {
+                add(&mut loads, *item, 1.0);
+                if fits(&loads) {
+                    self.packed[*item] = true;
+                } else {
+                    add(&mut loads, *item, -1.0);
+                }
+            }
+        }
+    }
+
+    /// This function offers some functionality for evaluation
+    fn evaluate(&mut self) {
+        let fx: f64 = self
+            .packed_items()
+            .map(|item| self.instance.values[item])
+            .sum();
+        let overload = self.get_overload();
+        self.objective_function_value = vec![fx; 1];
+        self.quality_scalar = if overload > 0.0 { -overload } else { fx };
+        self.constraint_violation = overload;
+    }
+}
+
+/// The amount that weights in a constraint are measured relative to, which is its capacity unless
+/// the capacity is zero, in which case weights are taken as they are
+fn relative_scale(capacity: f64) -> f64 {
+    if capacity > 0.0 {
+        capacity
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod knapsack_tests {
+    use crate::problems::{Knapsack, KnapsackInstance};
+    use crate::Solution;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_repair_makes_solutions_feasible() {
+        let mut solution = Knapsack::new();
+        solution.instance = Arc::new(KnapsackInstance::random(40, 3, 0.25, 1));
+        solution.packed = vec![true; 40];
+        solution.evaluate();
+        assert!(!solution.is_feasible());
+        assert!(solution.get_quality_scalar() < 0.0);
+        assert!(solution.get_constraint_violation() > 0.0);
+        solution.apply_move_operator(2, 1.0);
+        assert!(solution.is_feasible());
+        assert_eq!(solution.get_constraint_violation(), 0.0);
+        assert_eq!(solution.get_quality_scalar(), solution.get_total_value());
+    }
+
+    #[test]
+    fn test_constraints_without_capacity() {
+        let mut solution = Knapsack::new();
+        solution.instance = Arc::new(KnapsackInstance::new(
+            vec![10.0, 20.0, 30.0],
+            vec![vec![1.0, 0.0, 2.0], vec![1.0, 1.0, 1.0]],
+            vec![0.0, 2.0],
+        ));
+        solution.packed = vec![true; 3];
+        solution.evaluate();
+        assert_eq!(solution.get_constraint_violation(), 3.0 + 0.5);
+        solution.apply_move_operator(2, 1.0);
+        assert_eq!(solution.get_packed(), &[false, true, false]);
+        assert_eq!(solution.get_quality_scalar(), 20.0);
+    }
+}
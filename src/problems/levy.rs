@@ -0,0 +1,35 @@
+//! This is an example problem for optimizing the Levy function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Levy problem
+pub type Levy<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, LevyFunction>;
+
+/// This is the Levy function, a rugged landscape with its minimum at one in every dimension
+#[derive(Clone, Debug)]
+pub struct LevyFunction;
+
+impl TestFunction for LevyFunction {
+    const LOWER_BOUND: f64 = -10.0;
+    const UPPER_BOUND: f64 = 10.0;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        let w: Vec<f64> = x.iter().map(|xi| 1.0 + (xi - 1.0) / 4.0).collect();
+        let (first, last) = match (w.first(), w.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            // A point with no dimensions has nothing to minimize
+            _ => return 0.0,
+        };
+        let mut fx = (std::f64::consts::PI * first).sin().powi(2);
+        for wi in &w[..w.len() - 1] {
+            fx +=
+                (wi - 1.0).powi(2) * (1.0 + 10.0 * (std::f64::consts::PI * wi + 1.0).sin().powi(2));
+        }
+        fx + (last - 1.0).powi(2) * (1.0 + (2.0 * std::f64::consts::PI * last).sin().powi(2))
+    }
+
+    fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+        Some(0.0)
+    }
+}
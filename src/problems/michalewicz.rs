@@ -0,0 +1,40 @@
+//! This is an example problem for optimizing the Michalewicz function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// Steepness of the valleys and ridges
+const STEEPNESS: i32 = 10;
+
+/// This contains solutions for the Michalewicz problem
+pub type Michalewicz<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, MichalewiczFunction>;
+
+/// This is the Michalewicz function, which has steep valleys and ridges
+#[derive(Clone, Debug)]
+pub struct MichalewiczFunction;
+
+impl TestFunction for MichalewiczFunction {
+    const LOWER_BOUND: f64 = 0.0;
+    const UPPER_BOUND: f64 = std::f64::consts::PI;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        let mut fx = 0.0;
+        for (i, xi) in x.iter().enumerate() {
+            fx -= xi.sin()
+                * (((i + 1) as f64) * xi.powi(2) / std::f64::consts::PI)
+                    .sin()
+                    .powi(2 * STEEPNESS);
+        }
+        fx
+    }
+
+    fn optimal_objective_value(number_of_dimensions: usize) -> Option<f64> {
+        // The optimum has only been tabulated for a few dimensions
+        match number_of_dimensions {
+            2 => Some(-1.8013),
+            5 => Some(-4.687658),
+            10 => Some(-9.66015),
+            _ => None,
+        }
+    }
+}
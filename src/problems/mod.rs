@@ -1,8 +1,101 @@
 //! This module contains some pre-implemented problems for demonstration and testing purposes. To
 //! implement your own problems, simply create a class that implements the ```Solution``` trait.
+//!
+//! The continuous test functions also implement the ```Benchmark``` trait, which provides their
+//! bounds and known optimum so that results can be reported as a distance from the optimum. All
+//! but Ackley are a ```ContinuousBenchmark``` of a ```TestFunction```.
+
+mod shared_instance;
 
 mod structure;
 pub use structure::Structure;
 
+mod tsp;
+pub use tsp::{Tsp, TspInstance};
+
+mod knapsack;
+pub use knapsack::{Knapsack, KnapsackInstance};
+
+mod set_cover;
+pub use set_cover::{SetCover, SetCoverInstance};
+
+mod benchmark;
+pub use benchmark::Benchmark;
+
+mod bound_handling;
+pub use bound_handling::{BoundHandling, Clip, Reflect, Wrap};
+
+mod continuous;
+pub use continuous::{ContinuousBenchmark, TestFunction};
+
 mod ackley;
 pub use ackley::Ackley;
+
+mod sphere;
+pub use sphere::{Sphere, SphereFunction};
+
+mod rastrigin;
+pub use rastrigin::{Rastrigin, RastriginFunction};
+
+mod rosenbrock;
+pub use rosenbrock::{Rosenbrock, RosenbrockFunction};
+
+mod schwefel;
+pub use schwefel::{Schwefel, SchwefelFunction};
+
+mod griewank;
+pub use griewank::{Griewank, GriewankFunction};
+
+mod levy;
+pub use levy::{Levy, LevyFunction};
+
+mod michalewicz;
+pub use michalewicz::{Michalewicz, MichalewiczFunction};
+
+mod styblinski_tang;
+pub use styblinski_tang::{StyblinskiTang, StyblinskiTangFunction};
+
+#[cfg(test)]
+mod benchmark_tests {
+    use crate::problems::{
+        Ackley, Benchmark, Griewank, Levy, Michalewicz, Rastrigin, Rosenbrock, Schwefel, Sphere,
+        StyblinskiTang,
+    };
+
+    /// This checks that a solution sits at the known optimum
+    fn assert_optimal<B: Benchmark>(solution: B) {
+        let distance = solution.distance_from_optimum().unwrap();
+        assert!(
+            distance.abs() < 1e-3,
+            "distance from optimum was {}",
+            distance
+        );
+    }
+
+    #[test]
+    fn test_known_optima() {
+        assert_optimal(Ackley::<3>::from_point(vec![0.0; 3]));
+        assert_optimal(Sphere::<3>::from_point(vec![0.0; 3]));
+        assert_optimal(Rastrigin::<3>::from_point(vec![0.0; 3]));
+        assert_optimal(Rosenbrock::<3>::from_point(vec![1.0; 3]));
+        assert_optimal(Schwefel::<3>::from_point(vec![420.9687; 3]));
+        assert_optimal(Griewank::<3>::from_point(vec![0.0; 3]));
+        assert_optimal(Levy::<3>::from_point(vec![1.0; 3]));
+        assert_optimal(Michalewicz::<2>::from_point(vec![
+            2.20291,
+            std::f64::consts::FRAC_PI_2,
+        ]));
+        assert_optimal(StyblinskiTang::<3>::from_point(vec![-2.903534; 3]));
+    }
+
+    #[test]
+    fn test_points_without_dimensions() {
+        assert_optimal(Levy::<0>::from_point(vec![]));
+        assert_optimal(Sphere::<0>::from_point(vec![]));
+    }
+
+    #[test]
+    fn test_unknown_optimum() {
+        assert!(Michalewicz::<3>::optimal_objective_value().is_none());
+    }
+}
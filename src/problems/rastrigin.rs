@@ -0,0 +1,29 @@
+//! This is an example problem for optimizing the Rastrigin function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Rastrigin problem
+pub type Rastrigin<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, RastriginFunction>;
+
+/// This is the Rastrigin function, a sphere covered in regularly spaced local minima
+#[derive(Clone, Debug)]
+pub struct RastriginFunction;
+
+impl TestFunction for RastriginFunction {
+    const LOWER_BOUND: f64 = -5.12;
+    const UPPER_BOUND: f64 = 5.12;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        let n = x.len();
+        let mut fx = 10.0 * (n as f64);
+        for xi in x {
+            fx += xi.powi(2) - 10.0 * (2.0 * std::f64::consts::PI * xi).cos();
+        }
+        fx
+    }
+
+    fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+        Some(0.0)
+    }
+}
@@ -0,0 +1,26 @@
+//! This is an example problem for optimizing the Rosenbrock function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Rosenbrock problem
+pub type Rosenbrock<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, RosenbrockFunction>;
+
+/// This is the Rosenbrock function, whose minimum lies in a narrow, curved valley
+#[derive(Clone, Debug)]
+pub struct RosenbrockFunction;
+
+impl TestFunction for RosenbrockFunction {
+    const LOWER_BOUND: f64 = -5.0;
+    const UPPER_BOUND: f64 = 10.0;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        x.windows(2)
+            .map(|pair| 100.0 * (pair[1] - pair[0].powi(2)).powi(2) + (1.0 - pair[0]).powi(2))
+            .sum()
+    }
+
+    fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+        Some(0.0)
+    }
+}
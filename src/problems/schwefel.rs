@@ -0,0 +1,29 @@
+//! This is an example problem for optimizing the Schwefel function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Schwefel problem
+pub type Schwefel<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, SchwefelFunction>;
+
+/// This is the Schwefel function, whose best local minima are far apart
+#[derive(Clone, Debug)]
+pub struct SchwefelFunction;
+
+impl TestFunction for SchwefelFunction {
+    const LOWER_BOUND: f64 = -500.0;
+    const UPPER_BOUND: f64 = 500.0;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        let n = x.len();
+        let mut fx = 418.9829 * (n as f64);
+        for xi in x {
+            fx -= xi * xi.abs().sqrt().sin();
+        }
+        fx
+    }
+
+    fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+        Some(0.0)
+    }
+}
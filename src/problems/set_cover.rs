@@ -0,0 +1,385 @@
+//! This is an example problem for the (weighted) set-covering problem
+//!
+//! Because `Solution::new` takes no arguments, every `SetCover` solution is built on the instance
+//! that was most recently passed to `SetCover::set_instance`. If no instance has been set, a random
+//! instance with `DEFAULT_NUMBER_OF_ROWS` rows and `DEFAULT_NUMBER_OF_COLUMNS` columns is generated
+//! the first time one is needed.
+
+use super::{
+    super::utilities::{
+        parsing::{invalid_data, NumberReader},
+        randomness::{random_index, random_unit_draw, seeded_rng},
+        Solution,
+    },
+    shared_instance::SharedInstance,
+};
+use crate::CisatSolution;
+use rand_distr::{Distribution, Uniform};
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+/// Number of rows in the instance used when no other instance has been set
+const DEFAULT_NUMBER_OF_ROWS: usize = 50;
+
+/// Number of columns in the instance used when no other instance has been set
+const DEFAULT_NUMBER_OF_COLUMNS: usize = 200;
+
+/// The instance that new solutions are built on
+static INSTANCE: SharedInstance<SetCoverInstance> = SharedInstance::new();
+
+/// This contains a set-covering instance: rows that must be covered, and columns with a cost that
+/// each cover some of the rows
+#[derive(Clone)]
+pub struct SetCoverInstance {
+    /// The cost of each column
+    costs: Vec<f64>,
+    /// The rows covered by each column
+    rows_covered_by: Vec<Vec<usize>>,
+    /// The number of rows
+    number_of_rows: usize,
+}
+
+impl SetCoverInstance {
+    /// Makes an instance from the cost of each column and the rows that each column covers
+    pub fn new(costs: Vec<f64>, rows_covered_by: Vec<Vec<usize>>, number_of_rows: usize) -> Self {
+        assert_eq!(
+            costs.len(),
+            rows_covered_by.len(),
+            "There must be one list of rows for each column"
+        );
+        let mut covered = vec![false; number_of_rows];
+        for row in rows_covered_by.iter().flatten() {
+            covered[*row] = true;
+        }
+        assert!(
+            covered.iter().all(|x| *x),
+            "Every row must be covered by at least one column"
+        );
+        SetCoverInstance {
+            costs,
+            rows_covered_by,
+            number_of_rows,
+        }
+    }
+
+    /// Generates a random instance in which each column covers each row with probability
+    /// `density`, and costs are integers between 1 and 100. Rows that no column covers are given
+    /// to a random column.
+    /// ```
+    /// use cisat::problems::SetCoverInstance;
+    /// let instance = SetCoverInstance::random(20, 100, 0.05, 0);
+    /// assert_eq!(instance.get_number_of_columns(), 100);
+    /// ```
+    pub fn random(
+        number_of_rows: usize,
+        number_of_columns: usize,
+        density: f64,
+        seed: u64,
+    ) -> Self {
+        let mut rng = seeded_rng(Some(seed));
+        let unit = Uniform::new(0.0, 1.0);
+        let column_distribution = Uniform::new(0, number_of_columns);
+        let cost_distribution = Uniform::new_inclusive(1, 100);
+        let mut rows_covered_by: Vec<Vec<usize>> = (0..number_of_columns)
+            .map(|_| {
+                (0..number_of_rows)
+                    .filter(|_| unit.sample(&mut rng) < density)
+                    .collect()
+            })
+            .collect();
+        for row in 0..number_of_rows {
+            if !rows_covered_by.iter().any(|rows| rows.contains(&row)) {
+                rows_covered_by[column_distribution.sample(&mut rng)].push(row);
+            }
+        }
+        let costs = (0..number_of_columns)
+            .map(|_| cost_distribution.sample(&mut rng) as f64)
+            .collect();
+        SetCoverInstance::new(costs, rows_covered_by, number_of_rows)
+    }
+
+    /// Loads an instance from a file in the OR-Library set-covering format (as used by `scp41`
+    /// and the other `scp` files)
+    pub fn from_or_library_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        SetCoverInstance::from_or_library_str(&fs::read_to_string(path)?)
+    }
+
+    /// Parses an instance from the contents of an OR-Library set-covering file: the number of rows
+    /// and columns, the cost of each column, and then for each row the number of columns that
+    /// cover it followed by those columns, counting from one
+    /// ```
+    /// use cisat::problems::SetCoverInstance;
+    /// let contents = "2 3
+    /// 1 2 3
+    /// 2 1 3
+    /// 1 2";
+    /// let instance = SetCoverInstance::from_or_library_str(contents).unwrap();
+    /// assert_eq!(instance.get_number_of_rows(), 2);
+    /// ```
+    pub fn from_or_library_str(contents: &str) -> io::Result<Self> {
+        let mut reader = NumberReader::new(contents);
+        let number_of_rows: usize = reader.next()?;
+        let number_of_columns: usize = reader.next()?;
+        let costs = reader.next_n(number_of_columns)?;
+        let mut rows_covered_by = vec![vec![]; number_of_columns];
+        for row in 0..number_of_rows {
+            let count: usize = reader.next()?;
+            for column in reader.next_n::<usize>(count)? {
+                if column == 0 || column > number_of_columns {
+                    return Err(invalid_data(format!(
+                        "Column {} is out of range for row {}",
+                        column,
+                        row + 1
+                    )));
+                }
+                rows_covered_by[column - 1].push(row);
+            }
+            if count == 0 {
+                return Err(invalid_data(format!("Row {} is not covered", row + 1)));
+            }
+        }
+        Ok(SetCoverInstance::new(
+            costs,
+            rows_covered_by,
+            number_of_rows,
+        ))
+    }
+
+    /// The number of rows in the instance
+    pub fn get_number_of_rows(&self) -> usize {
+        self.number_of_rows
+    }
+
+    /// The number of columns in the instance
+    pub fn get_number_of_columns(&self) -> usize {
+        self.costs.len()
+    }
+}
+
+impl fmt::Debug for SetCoverInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetCoverInstance")
+            .field("number_of_rows", &self.get_number_of_rows())
+            .field("number_of_columns", &self.get_number_of_columns())
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, CisatSolution)]
+/// This contains solutions for the set-covering problem, as a choice of which columns to use.
+///
+/// Feasible solutions have the negative of their total cost as their quality. Infeasible
+/// solutions are scored below the cost of using every column, by the number of uncovered rows,
+/// so that every feasible solution is better than every infeasible one. The number of uncovered
+/// rows is also reported as the constraint violation.
+///
+/// The move operators are:
+/// - 0: Bit-flip, which adds or removes a single column
+/// - 1: Swap, which removes one column and adds another
+/// - 2: Repair, which greedily adds the cheapest columns per newly covered row until every row is
+///   covered, and then removes redundant columns, most expensive first
+///
+/// Solutions are built on the current instance:
+/// ```
+/// use cisat::{Parameters, Cohort, problems::{SetCover, SetCoverInstance}};
+/// SetCover::set_instance(SetCoverInstance::random(20, 60, 0.1, 0));
+/// let mut x = Cohort::<SetCover>::new(Parameters::default());
+/// x.solve();
+/// ```
+pub struct SetCover {
+    /// This contains the instance that the solution is for
+    instance: Arc<SetCoverInstance>,
+    /// This contains whether each column is used
+    selected: Vec<bool>,
+    /// This contains direct objective function values
+    objective_function_value: Vec<f64>,
+    /// This contains a single quality scalar derived from objective function values
+    #[quality]
+    quality_scalar: f64,
+    /// This contains the number of uncovered rows
+    constraint_violation: f64,
+}
+
+impl Solution for SetCover {
+    const NUMBER_OF_MOVE_OPERATORS: usize = 3;
+    const NUMBER_OF_OBJECTIVES: usize = 1;
+
+    fn new() -> SetCover {
+        // Start from a random selection, repaired to feasibility
+        let instance = SetCover::get_instance();
+        let mut solution = SetCover {
+            selected: (0..instance.get_number_of_columns())
+                .map(|_| random_unit_draw() < 0.5)
+                .collect(),
+            instance,
+            objective_function_value: vec![0.0; 1],
+            quality_scalar: 0.0,
+            constraint_violation: 0.0,
+        };
+        solution.repair();
+        solution.evaluate();
+        solution
+    }
+
+    fn apply_move_operator(&mut self, move_index: usize, _temperature: f64) {
+        match move_index {
+            0 => self.bit_flip(),
+            1 => self.swap(),
+            2 => self.repair(),
+            _ => panic!(
+                "The move index {} is not in the valid range [0, {})",
+                move_index,
+                SetCover::NUMBER_OF_MOVE_OPERATORS
+            ),
+        }
+        self.evaluate();
+    }
+
+    fn get_quality_scalar(&self) -> f64 {
+        self.quality_scalar
+    }
+
+    fn get_constraint_violation(&self) -> f64 {
+        self.constraint_violation
+    }
+}
+
+impl SetCover {
+    /// Sets the instance that new solutions are built on, across the whole process. Solutions that
+    /// already exist keep the instance they were built on.
+    pub fn set_instance(instance: SetCoverInstance) {
+        INSTANCE.set(instance);
+    }
+
+    /// Gets the instance that new solutions are built on, generating the default instance if none
+    /// has been set
+    pub fn get_instance() -> Arc<SetCoverInstance> {
+        INSTANCE.get_or_insert_with(|| {
+            SetCoverInstance::random(DEFAULT_NUMBER_OF_ROWS, DEFAULT_NUMBER_OF_COLUMNS, 0.05, 0)
+        })
+    }
+
+    /// Whether each column is used
+    pub fn get_selected(&self) -> &[bool] {
+        &self.selected
+    }
+
+    /// The total cost of the selected columns
+    pub fn get_total_cost(&self) -> f64 {
+        self.objective_function_value[0]
+    }
+
+    /// Whether every row is covered
+    pub fn is_feasible(&self) -> bool {
+        self.count_uncovered_rows() == 0
+    }
+
+    /// The number of times each row is covered by the selected columns
+    fn coverage(&self) -> Vec<usize> {
+        let mut coverage = vec![0; self.instance.number_of_rows];
+        for (column, rows) in self.instance.rows_covered_by.iter().enumerate() {
+            if self.selected[column] {
+                rows.iter().for_each(|row| coverage[*row] += 1);
+            }
+        }
+        coverage
+    }
+
+    /// The number of rows that no selected column covers
+    fn count_uncovered_rows(&self) -> usize {
+        self.coverage().iter().filter(|x| **x == 0).count()
+    }
+
+    /// Adds or removes a single random column
+    fn bit_flip(&mut self) {
+        let column = random_index(self.selected.len());
+        self.selected[column] = !self.selected[column];
+    }
+
+    /// Removes a random selected column and adds a random unselected column
+    fn swap(&mut self) {
+        let selected: Vec<usize> = (0..self.selected.len())
+            .filter(|column| self.selected[*column])
+            .collect();
+        let unselected: Vec<usize> = (0..self.selected.len())
+            .filter(|column| !self.selected[*column])
+            .collect();
+        if selected.is_empty() || unselected.is_empty() {
+            self.bit_flip();
+            return;
+        }
+        self.selected[selected[random_index(selected.len())]] = false;
+        self.selected[unselected[random_index(unselected.len())]] = true;
+    }
+
+    /// Greedily covers every row, then removes redundant columns
+    fn repair(&mut self) {
+        let mut coverage = self.coverage();
+
+        // Add the column with the lowest cost per newly covered row until every row is covered
+        while coverage.contains(&0) {
+            let (column, _) = (0..self.selected.len())
+                .filter(|column| !self.selected[*column])
+                .map(|column| {
+                    let newly_covered = self.instance.rows_covered_by[column]
+                        .iter()
+                        .filter(|row| coverage[**row] == 0)
+                        .count();
+                    (column, self.instance.costs[column] / newly_covered as f64)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            self.selected[column] = true;
+            self.instance.rows_covered_by[column]
+                .iter()
+                .for_each(|row| coverage[*row] += 1);
+        }
+
+        // Remove columns whose rows are all covered elsewhere, most expensive first
+        let mut columns: Vec<usize> = (0..self.selected.len())
+            .filter(|column| self.selected[*column])
+            .collect();
+        columns.sort_by(|a, b| self.instance.costs[*b].total_cmp(&self.instance.costs[*a]));
+        for column in columns {
+            let rows = &self.instance.rows_covered_by[column];
+            if rows.iter().all(|row| coverage[*row] > 1) {
+                self.selected[column] = false;
+                rows.iter().for_each(|row| coverage[*row] -= 1);
+            }
+        }
+    }
+
+    /// This function offers some functionality for evaluation
+    fn evaluate(&mut self) {
+        let fx: f64 = (0..self.selected.len())
+            .filter(|column| self.selected[*column])
+            .map(|column| self.instance.costs[column])
+            .sum();
+        let uncovered_rows = self.count_uncovered_rows();
+        self.objective_function_value = vec![fx; 1];
+        self.quality_scalar = if uncovered_rows > 0 {
+            -(self.instance.costs.iter().sum::<f64>() + uncovered_rows as f64)
+        } else {
+            -fx
+        };
+        self.constraint_violation = uncovered_rows as f64;
+    }
+}
+
+#[cfg(test)]
+mod set_cover_tests {
+    use crate::problems::{SetCover, SetCoverInstance};
+    use crate::Solution;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_repair_makes_solutions_feasible() {
+        let mut solution = SetCover::new();
+        solution.instance = Arc::new(SetCoverInstance::random(30, 80, 0.05, 1));
+        solution.selected = vec![false; 80];
+        solution.evaluate();
+        assert!(!solution.is_feasible());
+        solution.apply_move_operator(2, 1.0);
+        assert!(solution.is_feasible());
+        assert_eq!(solution.get_quality_scalar(), -solution.get_total_cost());
+    }
+}
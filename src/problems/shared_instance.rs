@@ -0,0 +1,38 @@
+//! This module contains the storage for the instance that new solutions of a problem are built on
+
+use std::sync::{Arc, RwLock};
+
+/// This holds the instance that new solutions of a problem are built on. `Solution::new` takes no
+/// arguments, so each problem that reads an instance keeps one of these in a static. The instance
+/// is shared by the whole process, and solutions hold on to the instance they were built on, so
+/// setting a new one only affects solutions built afterwards.
+pub(crate) struct SharedInstance<T> {
+    /// The instance, once one has been set or generated
+    instance: RwLock<Option<Arc<T>>>,
+}
+
+impl<T> SharedInstance<T> {
+    /// Makes an empty slot for an instance
+    pub(crate) const fn new() -> Self {
+        SharedInstance {
+            instance: RwLock::new(None),
+        }
+    }
+
+    /// Replaces the instance
+    pub(crate) fn set(&self, instance: T) {
+        *self.instance.write().unwrap() = Some(Arc::new(instance));
+    }
+
+    /// Gets the instance, generating it with `default` if none has been set
+    pub(crate) fn get_or_insert_with(&self, default: impl FnOnce() -> T) -> Arc<T> {
+        if let Some(instance) = self.instance.read().unwrap().as_ref() {
+            return instance.clone();
+        }
+        self.instance
+            .write()
+            .unwrap()
+            .get_or_insert_with(|| Arc::new(default()))
+            .clone()
+    }
+}
@@ -0,0 +1,24 @@
+//! This is an example problem for optimizing the Sphere function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Sphere problem
+pub type Sphere<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, SphereFunction>;
+
+/// This is the Sphere function, the sum of squares
+#[derive(Clone, Debug)]
+pub struct SphereFunction;
+
+impl TestFunction for SphereFunction {
+    const LOWER_BOUND: f64 = -5.12;
+    const UPPER_BOUND: f64 = 5.12;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        x.iter().map(|xi| xi.powi(2)).sum()
+    }
+
+    fn optimal_objective_value(_number_of_dimensions: usize) -> Option<f64> {
+        Some(0.0)
+    }
+}
@@ -1,7 +1,7 @@
 //! This is an example problem for designing truss structures
 use super::super::utilities::Solution;
 use crate::utilities::randomness::multinomial_tuple_draw;
-use std::{cmp::Ordering, ops::Sub};
+use crate::CisatSolution;
 use trussx::{StructuralShape, Truss};
 
 /// Member radius and wall thickness
@@ -12,15 +12,26 @@ const MEMBER_RADIUS: [f64; 10] = [
 /// Radius thickness ratio for pipes
 const RADIUS_THICKNESS_RATIO: f64 = 7.5;
 
-#[derive(Clone, Debug)]
+/// Factor of safety that every member must reach
+const MINIMUM_FACTOR_OF_SAFETY: f64 = 1.25;
+
+#[derive(Clone, Debug, CisatSolution)]
 /// This is a structure!
+///
+/// Structures do not recombine, so agents always adopt a teammate's structure wholesale, whatever
+/// the crossover rate. Recombining trusses by the union or intersection of their member sets would
+/// need the joints and members of each truss, and `trussx` has no way to list or copy them, so that
+/// crossover is out of scope until it does.
 pub struct Structure {
     /// This contains the fundamental truss information
     truss: Truss,
     /// This contains a single quality scalar derived from objective function values
+    #[quality]
     quality_scalar: f64,
     /// This contains direct objective function values
     objective_function_value: Vec<f64>,
+    /// This contains the total shortfall of the members from the minimum factor of safety
+    constraint_violation: f64,
 }
 
 impl Solution for Structure {
@@ -57,11 +68,14 @@ impl Solution for Structure {
         // TODO: Add more nodes, connect them, set reactions, set forces
 
         // Return the new truss
-        Structure {
+        let mut structure = Structure {
             truss: x,
             quality_scalar: 0.0,
             objective_function_value: vec![0.0; Structure::NUMBER_OF_OBJECTIVES],
-        }
+            constraint_violation: 0.0,
+        };
+        structure.evaluate();
+        structure
     }
 
     fn apply_move_operator(&mut self, move_index: usize, _temperature: f64) {
@@ -79,11 +93,18 @@ impl Solution for Structure {
                 Structure::NUMBER_OF_MOVE_OPERATORS
             ),
         }
+        self.evaluate();
     }
 
     fn get_quality_scalar(&self) -> f64 {
         self.quality_scalar
     }
+
+    fn get_constraint_violation(&self) -> f64 {
+        self.constraint_violation
+    }
+
+    // Crossover keeps the default of `None`, as the truss can not be taken apart member by member
 }
 
 impl Structure {
@@ -119,32 +140,14 @@ impl Structure {
     fn move_joint(&mut self) {
         unimplemented!()
     }
-}
-
-impl PartialEq for Structure {
-    fn eq(&self, other: &Self) -> bool {
-        self.quality_scalar == other.quality_scalar
-    }
-}
-
-impl Eq for Structure {}
-
-impl PartialOrd for Structure {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.quality_scalar.partial_cmp(&other.quality_scalar)
-    }
-}
-
-impl Ord for Structure {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-impl Sub for Structure {
-    type Output = f64;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.quality_scalar - rhs.quality_scalar
+    /// This function offers some functionality for evaluation
+    fn evaluate(&mut self) {
+        self.constraint_violation = self
+            .truss
+            .get_fos()
+            .iter()
+            .map(|fos| (MINIMUM_FACTOR_OF_SAFETY - fos).max(0.0))
+            .sum();
     }
 }
@@ -0,0 +1,27 @@
+//! This is an example problem for optimizing the Styblinski-Tang function
+
+use super::{ContinuousBenchmark, TestFunction};
+
+/// This contains solutions for the Styblinski-Tang problem
+pub type StyblinskiTang<const NUMBER_OF_DIMENSIONS: usize> =
+    ContinuousBenchmark<NUMBER_OF_DIMENSIONS, StyblinskiTangFunction>;
+
+/// This is the Styblinski-Tang function, a sum of one quartic per dimension
+#[derive(Clone, Debug)]
+pub struct StyblinskiTangFunction;
+
+impl TestFunction for StyblinskiTangFunction {
+    const LOWER_BOUND: f64 = -5.0;
+    const UPPER_BOUND: f64 = 5.0;
+
+    fn evaluate(x: &[f64]) -> f64 {
+        0.5 * x
+            .iter()
+            .map(|xi| xi.powi(4) - 16.0 * xi.powi(2) + 5.0 * xi)
+            .sum::<f64>()
+    }
+
+    fn optimal_objective_value(number_of_dimensions: usize) -> Option<f64> {
+        Some(-39.16616570377142 * (number_of_dimensions as f64))
+    }
+}
@@ -0,0 +1,460 @@
+//! This is an example problem for finding short tours in the traveling salesman problem
+//!
+//! Because `Solution::new` takes no arguments, every `Tsp` solution is built on the instance that
+//! was most recently passed to `Tsp::set_instance`. If no instance has been set, a random Euclidean
+//! instance with `DEFAULT_NUMBER_OF_CITIES` cities is generated the first time one is needed.
+
+use super::{
+    super::utilities::{
+        parsing::{invalid_data, parse_number},
+        randomness::{random_index, random_permutation, seeded_rng},
+        Solution,
+    },
+    shared_instance::SharedInstance,
+};
+use crate::CisatSolution;
+use rand_distr::{Distribution, Uniform};
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+/// Number of cities in the instance used when no other instance has been set
+const DEFAULT_NUMBER_OF_CITIES: usize = 30;
+
+/// Longest segment that the or-opt move operator will relocate
+const MAXIMUM_OR_OPT_SEGMENT: usize = 3;
+
+/// The instance that new solutions are built on
+static INSTANCE: SharedInstance<TspInstance> = SharedInstance::new();
+
+/// This contains a traveling salesman problem instance, stored as a matrix of distances
+#[derive(Clone)]
+pub struct TspInstance {
+    /// The name of the instance
+    name: String,
+    /// The distance between every pair of cities
+    distances: Vec<Vec<f64>>,
+}
+
+impl TspInstance {
+    /// Generates an instance with cities placed uniformly at random in a 1000 by 1000 square, with
+    /// Euclidean distances between them
+    /// ```
+    /// use cisat::problems::TspInstance;
+    /// let instance = TspInstance::random_euclidean(10, 0);
+    /// assert_eq!(instance.get_number_of_cities(), 10);
+    /// ```
+    pub fn random_euclidean(number_of_cities: usize, seed: u64) -> Self {
+        let mut rng = seeded_rng(Some(seed));
+        let uniform = Uniform::new_inclusive(0.0, 1000.0);
+        let coordinates: Vec<(f64, f64)> = (0..number_of_cities)
+            .map(|_| (uniform.sample(&mut rng), uniform.sample(&mut rng)))
+            .collect();
+        TspInstance::from_distance_function(
+            format!("random{}", number_of_cities),
+            number_of_cities,
+            |i, j| {
+                let (xd, yd) = (
+                    coordinates[i].0 - coordinates[j].0,
+                    coordinates[i].1 - coordinates[j].1,
+                );
+                (xd * xd + yd * yd).sqrt()
+            },
+        )
+    }
+
+    /// Loads a symmetric instance from a TSPLIB file. Coordinates with the `EUC_2D`, `CEIL_2D`,
+    /// `ATT` and `GEO` edge weight types are supported, as are `EXPLICIT` edge weights in any of
+    /// the `FULL_MATRIX`, `UPPER_ROW`, `LOWER_ROW`, `UPPER_DIAG_ROW` and `LOWER_DIAG_ROW` formats.
+    pub fn from_tsplib_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        TspInstance::from_tsplib_str(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a symmetric instance from the contents of a TSPLIB file
+    /// ```
+    /// use cisat::problems::TspInstance;
+    /// let contents = "NAME : square
+    /// TYPE : TSP
+    /// DIMENSION : 4
+    /// EDGE_WEIGHT_TYPE : EUC_2D
+    /// NODE_COORD_SECTION
+    /// 1 0 0
+    /// 2 0 10
+    /// 3 10 10
+    /// 4 10 0
+    /// EOF";
+    /// let instance = TspInstance::from_tsplib_str(contents).unwrap();
+    /// assert_eq!(instance.get_distance(0, 2), 14.0);
+    /// ```
+    pub fn from_tsplib_str(contents: &str) -> io::Result<Self> {
+        let mut name = String::from("unnamed");
+        let mut dimension = None;
+        let mut edge_weight_type = String::new();
+        let mut edge_weight_format = String::from("FULL_MATRIX");
+        let mut coordinates = vec![];
+        let mut weights = vec![];
+
+        // Read the specification part, then the data sections
+        let mut section = "";
+        for line in contents.lines().map(str::trim).filter(|x| !x.is_empty()) {
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim().to_string();
+                match key.trim() {
+                    "NAME" => name = value,
+                    "TYPE" if value != "TSP" => {
+                        return Err(invalid_data(format!(
+                            "{} instances are not supported",
+                            value
+                        )))
+                    }
+                    "DIMENSION" => dimension = Some(parse_number::<usize>(&value)?),
+                    "EDGE_WEIGHT_TYPE" => edge_weight_type = value,
+                    "EDGE_WEIGHT_FORMAT" => edge_weight_format = value,
+                    _ => {}
+                }
+                continue;
+            }
+            match line {
+                "NODE_COORD_SECTION" | "EDGE_WEIGHT_SECTION" | "DISPLAY_DATA_SECTION" => {
+                    section = line;
+                }
+                "EOF" => break,
+                _ => match section {
+                    "NODE_COORD_SECTION" => {
+                        let fields: Vec<&str> = line.split_whitespace().collect();
+                        if fields.len() < 3 {
+                            return Err(invalid_data(format!("Malformed coordinate: {}", line)));
+                        }
+                        coordinates.push((parse_number(fields[1])?, parse_number(fields[2])?));
+                    }
+                    "EDGE_WEIGHT_SECTION" => {
+                        for field in line.split_whitespace() {
+                            weights.push(parse_number(field)?);
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        let dimension = dimension.ok_or_else(|| invalid_data(String::from("Missing DIMENSION")))?;
+        match edge_weight_type.as_str() {
+            "EXPLICIT" => {
+                // Walk the matrix entries in the order the format stores them
+                let entries: Vec<(usize, usize)> = match edge_weight_format.as_str() {
+                    "FULL_MATRIX" => (0..dimension)
+                        .flat_map(|i| (0..dimension).map(move |j| (i, j)))
+                        .collect(),
+                    "UPPER_ROW" => (0..dimension)
+                        .flat_map(|i| (i + 1..dimension).map(move |j| (i, j)))
+                        .collect(),
+                    "LOWER_ROW" => (0..dimension)
+                        .flat_map(|i| (0..i).map(move |j| (i, j)))
+                        .collect(),
+                    "UPPER_DIAG_ROW" => (0..dimension)
+                        .flat_map(|i| (i..dimension).map(move |j| (i, j)))
+                        .collect(),
+                    "LOWER_DIAG_ROW" => (0..dimension)
+                        .flat_map(|i| (0..=i).map(move |j| (i, j)))
+                        .collect(),
+                    format => {
+                        return Err(invalid_data(format!(
+                            "The {} edge weight format is not supported",
+                            format
+                        )))
+                    }
+                };
+                if weights.len() < entries.len() {
+                    return Err(invalid_data(format!(
+                        "Expected {} edge weights but found {}",
+                        entries.len(),
+                        weights.len()
+                    )));
+                }
+                let mut distances = vec![vec![0.0; dimension]; dimension];
+                for ((i, j), weight) in entries.into_iter().zip(weights) {
+                    distances[i][j] = weight;
+                    distances[j][i] = weight;
+                }
+                Ok(TspInstance { name, distances })
+            }
+            "EUC_2D" | "CEIL_2D" | "ATT" | "GEO" => {
+                if coordinates.len() != dimension {
+                    return Err(invalid_data(format!(
+                        "Expected {} coordinates but found {}",
+                        dimension,
+                        coordinates.len()
+                    )));
+                }
+                let distance = match edge_weight_type.as_str() {
+                    "EUC_2D" => euclidean_distance,
+                    "CEIL_2D" => ceiling_distance,
+                    "ATT" => pseudo_euclidean_distance,
+                    _ => geographical_distance,
+                };
+                Ok(TspInstance::from_distance_function(
+                    name,
+                    dimension,
+                    |i, j| distance(coordinates[i], coordinates[j]),
+                ))
+            }
+            _ => Err(invalid_data(format!(
+                "The {} edge weight type is not supported",
+                edge_weight_type
+            ))),
+        }
+    }
+
+    /// The name of the instance
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of cities in the instance
+    pub fn get_number_of_cities(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// The distance between two cities
+    pub fn get_distance(&self, from: usize, to: usize) -> f64 {
+        self.distances[from][to]
+    }
+
+    /// Builds an instance by tabulating a distance function
+    fn from_distance_function<F: Fn(usize, usize) -> f64>(
+        name: String,
+        number_of_cities: usize,
+        distance: F,
+    ) -> Self {
+        TspInstance {
+            name,
+            distances: (0..number_of_cities)
+                .map(|i| (0..number_of_cities).map(|j| distance(i, j)).collect())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Debug for TspInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TspInstance")
+            .field("name", &self.name)
+            .field("number_of_cities", &self.get_number_of_cities())
+            .finish()
+    }
+}
+
+/// TSPLIB `EUC_2D` distance, rounded to the nearest integer
+fn euclidean_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt().round()
+}
+
+/// TSPLIB `CEIL_2D` distance, rounded up to the next integer
+fn ceiling_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt().ceil()
+}
+
+/// TSPLIB `ATT` pseudo-Euclidean distance
+fn pseudo_euclidean_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let r = (((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)) / 10.0).sqrt();
+    let t = r.round();
+    if t < r {
+        t + 1.0
+    } else {
+        t
+    }
+}
+
+/// TSPLIB `GEO` distance, for coordinates given as latitude and longitude in DDD.MM format
+fn geographical_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    // TSPLIB uses these rounded constants, so they are reproduced exactly
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    const EARTH_RADIUS: f64 = 6378.388;
+    let to_radians = |x: f64| {
+        let degrees = x.trunc();
+        PI * (degrees + 5.0 * (x - degrees) / 3.0) / 180.0
+    };
+    let (latitude_a, longitude_a) = (to_radians(a.0), to_radians(a.1));
+    let (latitude_b, longitude_b) = (to_radians(b.0), to_radians(b.1));
+    let q1 = (longitude_a - longitude_b).cos();
+    let q2 = (latitude_a - latitude_b).cos();
+    let q3 = (latitude_a + latitude_b).cos();
+    (EARTH_RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
+
+#[derive(Clone, Debug, CisatSolution)]
+/// This contains solutions for the traveling salesman problem, as a tour that visits every city
+/// once and returns to the start.
+///
+/// The move operators are:
+/// - 0: 2-opt, which reverses a section of the tour
+/// - 1: Swap two cities
+/// - 2: Insertion, which moves a single city to another position
+/// - 3: Or-opt, which moves a segment of up to three cities to another position
+///
+/// Solutions are built on the current instance:
+/// ```
+/// use cisat::{Parameters, Cohort, problems::{Tsp, TspInstance}};
+/// Tsp::set_instance(TspInstance::random_euclidean(20, 0));
+/// let mut x = Cohort::<Tsp>::new(Parameters::default());
+/// x.solve();
+/// ```
+pub struct Tsp {
+    /// This contains the instance that the tour is for
+    instance: Arc<TspInstance>,
+    /// This contains the order in which cities are visited
+    tour: Vec<usize>,
+    /// This contains direct objective function values
+    objective_function_value: Vec<f64>,
+    /// This contains a single quality scalar derived from objective function values
+    #[quality]
+    quality_scalar: f64,
+}
+
+impl Solution for Tsp {
+    const NUMBER_OF_MOVE_OPERATORS: usize = 4;
+    const NUMBER_OF_OBJECTIVES: usize = 1;
+
+    fn new() -> Tsp {
+        let instance = Tsp::get_instance();
+        let mut solution = Tsp {
+            tour: random_permutation(instance.get_number_of_cities()),
+            instance,
+            objective_function_value: vec![0.0; 1],
+            quality_scalar: 0.0,
+        };
+        solution.evaluate();
+        solution
+    }
+
+    fn apply_move_operator(&mut self, move_index: usize, _temperature: f64) {
+        // Tours with fewer than four cities cannot be changed meaningfully
+        if self.tour.len() >= 4 {
+            match move_index {
+                0 => self.two_opt(),
+                1 => self.swap(),
+                2 => self.insertion(),
+                3 => self.or_opt(),
+                _ => panic!(
+                    "The move index {} is not in the valid range [0, {})",
+                    move_index,
+                    Tsp::NUMBER_OF_MOVE_OPERATORS
+                ),
+            }
+        }
+        self.evaluate();
+    }
+
+    fn get_quality_scalar(&self) -> f64 {
+        self.quality_scalar
+    }
+}
+
+impl Tsp {
+    /// Sets the instance that new solutions are built on, across the whole process. Solutions that
+    /// already exist keep the instance they were built on.
+    pub fn set_instance(instance: TspInstance) {
+        INSTANCE.set(instance);
+    }
+
+    /// Gets the instance that new solutions are built on, generating the default instance if none
+    /// has been set
+    pub fn get_instance() -> Arc<TspInstance> {
+        INSTANCE.get_or_insert_with(|| TspInstance::random_euclidean(DEFAULT_NUMBER_OF_CITIES, 0))
+    }
+
+    /// The order in which cities are visited
+    pub fn get_tour(&self) -> &[usize] {
+        &self.tour
+    }
+
+    /// The length of the tour, including the return to the first city
+    pub fn get_tour_length(&self) -> f64 {
+        self.objective_function_value[0]
+    }
+
+    /// Picks two distinct positions in the tour, in increasing order
+    fn random_positions(&self) -> (usize, usize) {
+        let n = self.tour.len();
+        let i = random_index(n);
+        let j = (i + 1 + random_index(n - 1)) % n;
+        (i.min(j), i.max(j))
+    }
+
+    /// Reverses the section of the tour between two positions
+    fn two_opt(&mut self) {
+        let (i, j) = self.random_positions();
+        self.tour[i..=j].reverse();
+    }
+
+    /// Swaps the cities at two positions
+    fn swap(&mut self) {
+        let (i, j) = self.random_positions();
+        self.tour.swap(i, j);
+    }
+
+    /// Moves a single city to another position
+    fn insertion(&mut self) {
+        let city = self.tour.remove(random_index(self.tour.len()));
+        self.tour.insert(random_index(self.tour.len() + 1), city);
+    }
+
+    /// Moves a segment of consecutive cities to another position
+    fn or_opt(&mut self) {
+        let length = 1 + random_index(MAXIMUM_OR_OPT_SEGMENT.min(self.tour.len() - 1));
+        let start = random_index(self.tour.len() - length + 1);
+        let segment: Vec<usize> = self.tour.drain(start..start + length).collect();
+        let destination = random_index(self.tour.len() + 1);
+        self.tour.splice(destination..destination, segment);
+    }
+
+    /// This function offers some functionality for evaluation
+    fn evaluate(&mut self) {
+        let n = self.tour.len();
+        let fx: f64 = (0..n)
+            .map(|i| {
+                self.instance
+                    .get_distance(self.tour[i], self.tour[(i + 1) % n])
+            })
+            .sum();
+        self.objective_function_value = vec![fx; 1];
+        self.quality_scalar = -fx;
+    }
+}
+
+#[cfg(test)]
+mod tsp_tests {
+    use crate::problems::{Tsp, TspInstance};
+    use crate::Solution;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_move_operators_keep_a_permutation() {
+        let mut solution = Tsp::new();
+        solution.instance = Arc::new(TspInstance::random_euclidean(12, 1));
+        solution.tour = (0..12).collect();
+        for move_index in 0..Tsp::NUMBER_OF_MOVE_OPERATORS {
+            for _ in 0..100 {
+                solution.apply_move_operator(move_index, 1.0);
+                let mut cities = solution.get_tour().to_vec();
+                cities.sort_unstable();
+                assert_eq!(cities, (0..12).collect::<Vec<usize>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_explicit_formats_agree() {
+        let full = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0 1 2\n1 0 3\n2 3 0\nEOF";
+        let upper = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\n3\nEOF";
+        let lower = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: LOWER_DIAG_ROW\nEDGE_WEIGHT_SECTION\n0 1 0 2 3 0\nEOF";
+        for contents in [full, upper, lower].iter() {
+            let instance = TspInstance::from_tsplib_str(contents).unwrap();
+            assert_eq!(instance.get_distance(0, 1), 1.0);
+            assert_eq!(instance.get_distance(2, 0), 2.0);
+            assert_eq!(instance.get_distance(1, 2), 3.0);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! This module contains the machinery for comparing solutions that may violate constraints
+
+use super::{parameters::ConstraintHandling, solution::compare_qualities, Solution};
+use rand::Rng;
+use std::{cmp::Ordering, collections::VecDeque};
+
+/// This applies a constraint-handling strategy to comparisons between solutions. It carries the
+/// state that adaptive penalties need, so each agent keeps its own.
+#[derive(Clone, Debug)]
+pub(crate) struct ConstraintHandler {
+    /// The strategy to apply
+    style: ConstraintHandling,
+    /// The current penalty coefficient
+    penalty: f64,
+    /// Whether each recent current solution was feasible, newest last
+    feasibility_history: VecDeque<bool>,
+}
+
+impl ConstraintHandler {
+    /// Makes a handler for a strategy
+    pub(crate) fn new(style: &ConstraintHandling) -> Self {
+        let penalty = match style {
+            ConstraintHandling::StaticPenalty { penalty } => *penalty,
+            ConstraintHandling::AdaptivePenalty {
+                initial_penalty, ..
+            } => *initial_penalty,
+            _ => 0.0,
+        };
+        ConstraintHandler {
+            style: style.clone(),
+            penalty,
+            feasibility_history: VecDeque::new(),
+        }
+    }
+
+    /// The quality of a solution less the penalty for its constraint violation
+    pub(crate) fn penalized_quality<S: Solution>(&self, solution: &S) -> f64 {
+        solution.get_quality_scalar() - self.penalty * solution.get_constraint_violation()
+    }
+
+    /// Compares two solutions, where `Ordering::Greater` means that `a` is better. This is
+    /// deterministic, so it is used to pick among candidates. Stochastic ranking picks by the
+    /// feasibility-first rules. Non-finite values are always worst.
+    pub(crate) fn compare<S: Solution>(&self, a: &S, b: &S) -> Ordering {
+        match self.style {
+            ConstraintHandling::StaticPenalty { .. }
+            | ConstraintHandling::AdaptivePenalty { .. } => {
+                compare_qualities(self.penalized_quality(a), self.penalized_quality(b))
+            }
+            ConstraintHandling::FeasibilityFirst | ConstraintHandling::StochasticRanking { .. } => {
+                compare_feasibility_first(a, b)
+            }
+            _ => a.cmp(b),
+        }
+    }
+
+    /// Compares two solutions to pick the best found so far, where `Ordering::Greater` means that
+    /// `a` is better. This is the same as `compare`, except that adaptive penalties rank by the
+    /// feasibility-first rules. An adaptive penalty differs between agents and changes over time,
+    /// so it would rank best solutions differently on a team than on its agents.
+    pub(crate) fn compare_best<S: Solution>(&self, a: &S, b: &S) -> Ordering {
+        match self.style {
+            ConstraintHandling::AdaptivePenalty { .. } => compare_feasibility_first(a, b),
+            _ => self.compare(a, b),
+        }
+    }
+
+    /// The improvement of `candidate` over `current` used in the acceptance test, where positive
+    /// values are always accepted and negative values are accepted with the Metropolis probability.
+    /// Stochastic ranking draws from `rng`.
+    pub(crate) fn acceptance_delta<S: Solution, R: Rng + ?Sized>(
+        &self,
+        candidate: &S,
+        current: &S,
+        rng: &mut R,
+    ) -> f64 {
+        let quality_delta = candidate.get_quality_scalar() - current.get_quality_scalar();
+        let (violation_candidate, violation_current) = (
+            candidate.get_constraint_violation(),
+            current.get_constraint_violation(),
+        );
+        let both_feasible = violation_candidate == 0.0 && violation_current == 0.0;
+        match self.style {
+            ConstraintHandling::StaticPenalty { .. }
+            | ConstraintHandling::AdaptivePenalty { .. } => {
+                self.penalized_quality(candidate) - self.penalized_quality(current)
+            }
+            ConstraintHandling::FeasibilityFirst => {
+                if both_feasible {
+                    quality_delta
+                } else if violation_current == 0.0 {
+                    // Never trade a feasible solution for an infeasible one
+                    f64::NEG_INFINITY
+                } else {
+                    violation_current - violation_candidate
+                }
+            }
+            ConstraintHandling::StochasticRanking { probability } => {
+                if both_feasible || rng.gen::<f64>() < probability {
+                    quality_delta
+                } else {
+                    violation_current - violation_candidate
+                }
+            }
+            _ => quality_delta,
+        }
+    }
+
+    /// Records whether the current solution is feasible, and adapts the penalty if every solution
+    /// in the window was feasible (the penalty is relaxed) or infeasible (the penalty is raised)
+    pub(crate) fn update<S: Solution>(&mut self, current: &S) {
+        if let ConstraintHandling::AdaptivePenalty {
+            decrease_factor,
+            increase_factor,
+            window,
+            ..
+        } = self.style
+        {
+            self.feasibility_history
+                .push_back(current.get_constraint_violation() == 0.0);
+            if self.feasibility_history.len() > window {
+                self.feasibility_history.pop_front();
+            }
+            if self.feasibility_history.len() == window {
+                if self.feasibility_history.iter().all(|x| *x) {
+                    self.penalty /= decrease_factor;
+                } else if self.feasibility_history.iter().all(|x| !*x) {
+                    self.penalty *= increase_factor;
+                }
+            }
+        }
+    }
+}
+
+/// Compares two solutions by Deb's rules, where feasible solutions are compared by quality and
+/// infeasible ones by violation
+fn compare_feasibility_first<S: Solution>(a: &S, b: &S) -> Ordering {
+    let (violation_a, violation_b) = (a.get_constraint_violation(), b.get_constraint_violation());
+    if violation_a == 0.0 && violation_b == 0.0 {
+        a.cmp(b)
+    } else {
+        compare_qualities(-violation_a, -violation_b)
+    }
+}
+
+#[cfg(test)]
+mod constraint_tests {
+    use crate::{
+        utilities::randomness::random_unit_draw, Agent, CisatSolution, ConstraintHandling,
+        Parameters, Solution, Team, TeamMethods,
+    };
+
+    /// This is a problem that starts out infeasible, and whose quality rewards going further into
+    /// the infeasible region, so only the constraint handling can bring it back
+    #[derive(Clone, Debug, CisatSolution)]
+    struct Overload {
+        /// The load, which is feasible up to 1
+        load: f64,
+        /// The quality of the solution, which is the load
+        #[quality]
+        quality_scalar: f64,
+    }
+
+    impl Solution for Overload {
+        const NUMBER_OF_MOVE_OPERATORS: usize = 1;
+        const NUMBER_OF_OBJECTIVES: usize = 1;
+
+        fn new() -> Self {
+            Overload {
+                load: 2.0,
+                quality_scalar: 2.0,
+            }
+        }
+
+        fn apply_move_operator(&mut self, _move_index: usize, _temperature: f64) {
+            self.load = (self.load + random_unit_draw() - 0.5).clamp(-5.0, 5.0);
+            self.quality_scalar = self.load;
+        }
+
+        fn get_quality_scalar(&self) -> f64 {
+            self.quality_scalar
+        }
+
+        fn get_constraint_violation(&self) -> f64 {
+            (self.load - 1.0).max(0.0)
+        }
+    }
+
+    #[test]
+    fn test_constrained_teams_find_feasible_solutions() {
+        // Without constraint handling, the team chases quality into the infeasible region
+        let mut team = Team::<Overload, Agent<Overload>>::new(Parameters::default().with_seed(7));
+        team.solve();
+        assert!(team.get_best_solution_so_far().get_constraint_violation() > 0.0);
+
+        for constraint_handling in &[
+            ConstraintHandling::StaticPenalty { penalty: 100.0 },
+            ConstraintHandling::AdaptivePenalty {
+                initial_penalty: 1.0,
+                decrease_factor: 2.0,
+                increase_factor: 3.0,
+                window: 10,
+            },
+            ConstraintHandling::FeasibilityFirst,
+            ConstraintHandling::StochasticRanking { probability: 0.45 },
+        ] {
+            let parameters = Parameters::default()
+                .with_seed(7)
+                .with_constraint_handling(constraint_handling.clone());
+            let mut team = Team::<Overload, Agent<Overload>>::new(parameters);
+            team.solve();
+            let best = team.get_best_solution_so_far();
+            assert_eq!(best.get_constraint_violation(), 0.0);
+            assert!(best.get_quality_scalar() > 0.5);
+        }
+    }
+}
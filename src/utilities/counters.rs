@@ -0,0 +1,163 @@
+//! This module contains the Counters struct, which keeps track of how much work has been done
+
+use std::fmt;
+use std::ops::AddAssign;
+
+/// This struct counts evaluations, move applications and communication events. Every application
+/// of a move operator is assumed to cost exactly one evaluation, as does every call to
+/// `Solution::new`.
+///
+/// Counters are kept by each agent and summed by teams and cohorts, which makes it possible to run
+/// against a fixed evaluation budget. See `Parameters::evaluation_budget` for how far a team may
+/// go over it. With default parameters, that is at most one evaluation per agent:
+/// ```
+/// use cisat::{Parameters, Cohort, problems::Ackley};
+/// let parameters = Parameters::default().with_evaluation_budget(150);
+/// let mut x = Cohort::<Ackley<5>>::new(parameters.clone());
+/// x.solve();
+/// assert!(x.get_counters().evaluations < 150 + parameters.number_of_agents);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Counters {
+    /// Number of function evaluations
+    pub evaluations: usize,
+    /// Number of applications of each move operator, indexed by move operator
+    pub move_applications: Vec<usize>,
+    /// Number of communication events participated in
+    pub communications: usize,
+    /// Number of communication events that resulted in adopting a teammate's solution
+    pub adoptions: usize,
+    /// Number of evaluations charged for time spent in meetings, which are included in
+    /// `evaluations`
+    pub meeting_evaluations: usize,
+    /// Number of communication events that resulted in recombining a teammate's solution with
+    /// our own
+    pub crossovers: usize,
+    /// Number of evaluations that gave a NaN or infinite quality
+    pub non_finite_qualities: usize,
+    /// Number of times a stagnating agent was reheated
+    pub reheats: usize,
+    /// Number of times a stagnating agent restarted from a new solution or the team's best
+    pub restarts: usize,
+}
+
+impl Counters {
+    /// Generates a blank set of counters for a problem with the given number of move operators
+    pub fn new(number_of_move_operators: usize) -> Self {
+        Counters {
+            move_applications: vec![0; number_of_move_operators],
+            ..Default::default()
+        }
+    }
+
+    /// Records the generation of a new solution
+    pub(crate) fn count_new_solution(&mut self) {
+        self.evaluations += 1;
+    }
+
+    /// Records a single application of a move operator
+    pub(crate) fn count_move_application(&mut self, move_index: usize) {
+        if move_index >= self.move_applications.len() {
+            self.move_applications.resize(move_index + 1, 0);
+        }
+        self.move_applications[move_index] += 1;
+        self.evaluations += 1;
+    }
+
+    /// Records a communication event, and whether or not a teammate's solution was adopted
+    pub(crate) fn count_communication(&mut self, adopted: bool) {
+        self.communications += 1;
+        if adopted {
+            self.adoptions += 1;
+        }
+    }
+
+    /// Records evaluations charged for time spent in a meeting
+    pub(crate) fn count_meeting_evaluations(&mut self, evaluations: usize) {
+        self.meeting_evaluations += evaluations;
+        self.evaluations += evaluations;
+    }
+
+    /// Records the recombination of two solutions, which costs an evaluation
+    pub(crate) fn count_crossover(&mut self) {
+        self.crossovers += 1;
+        self.evaluations += 1;
+    }
+
+    /// Records an evaluation that gave a NaN or infinite quality
+    pub(crate) fn count_non_finite_quality(&mut self) {
+        self.non_finite_qualities += 1;
+    }
+
+    /// Records the reheating of a stagnating agent
+    pub(crate) fn count_reheat(&mut self) {
+        self.reheats += 1;
+    }
+
+    /// Records the restart of a stagnating agent
+    pub(crate) fn count_restart(&mut self) {
+        self.restarts += 1;
+    }
+
+    /// Total number of move applications over all move operators
+    pub fn total_move_applications(&self) -> usize {
+        self.move_applications.iter().sum()
+    }
+}
+
+impl AddAssign<&Counters> for Counters {
+    fn add_assign(&mut self, other: &Counters) {
+        if other.move_applications.len() > self.move_applications.len() {
+            self.move_applications
+                .resize(other.move_applications.len(), 0);
+        }
+        for (total, count) in self
+            .move_applications
+            .iter_mut()
+            .zip(other.move_applications.iter())
+        {
+            *total += count;
+        }
+        self.evaluations += other.evaluations;
+        self.communications += other.communications;
+        self.adoptions += other.adoptions;
+        self.meeting_evaluations += other.meeting_evaluations;
+        self.crossovers += other.crossovers;
+        self.non_finite_qualities += other.non_finite_qualities;
+        self.reheats += other.reheats;
+        self.restarts += other.restarts;
+    }
+}
+
+#[allow(unused_must_use)]
+impl fmt::Display for Counters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, " - {} evaluations", self.evaluations);
+        writeln!(f, " - {} move applications", self.total_move_applications());
+        for (move_index, count) in self.move_applications.iter().enumerate() {
+            writeln!(f, "    - move operator {} = {}", move_index, count);
+        }
+        writeln!(f, " - {} communications", self.communications);
+        writeln!(f, " - {} adoptions", self.adoptions);
+        if self.meeting_evaluations > 0 {
+            writeln!(
+                f,
+                " - {} evaluations spent in meetings",
+                self.meeting_evaluations
+            );
+        }
+        if self.crossovers > 0 {
+            writeln!(f, " - {} crossovers", self.crossovers);
+        }
+        if self.non_finite_qualities > 0 {
+            writeln!(f, " - {} non-finite qualities", self.non_finite_qualities);
+        }
+        if self.reheats > 0 {
+            writeln!(f, " - {} reheats", self.reheats);
+        }
+        if self.restarts > 0 {
+            writeln!(f, " - {} restarts", self.restarts);
+        }
+        Ok(())
+    }
+}
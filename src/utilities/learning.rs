@@ -0,0 +1,166 @@
+//! This module contains the matrices of move operator preferences that agents learn, and reading
+//! and writing them so they can be carried over between runs
+
+use super::parsing::{invalid_data, parse_number};
+use std::{fmt, fs, io, path::Path};
+
+/// This is a matrix of the move operator preferences an agent has learned. Multinomial learning
+/// gives a single row with the probability of choosing each operator, and Markov learning gives
+/// one row per operator with the probabilities of choosing each operator after it.
+///
+/// Learned matrices can be saved to a file and loaded into a new run, so that its agents start out
+/// with the experience of the old ones:
+/// ```
+/// use cisat::{Cohort, LearnedMatrix, OperationalLearning, Parameters, problems::Ackley};
+/// let parameters = Parameters {
+///     operational_learning: OperationalLearning::Markov {
+///         learning_rate: 0.05,
+///         initial_learning_matrix: vec![],
+///     },
+///     ..Parameters::default()
+/// };
+/// let mut x = Cohort::<Ackley<5>>::new(parameters.clone());
+/// x.solve();
+///
+/// // Start a new cohort from what the old one learned on average
+/// let contents = x.get_average_learned_matrix().unwrap().to_string();
+/// let learned = LearnedMatrix::average(&LearnedMatrix::parse_all(&contents).unwrap()).unwrap();
+/// let mut y = Cohort::<Ackley<5>>::new(parameters.with_learned_matrix(learned));
+/// y.solve();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LearnedMatrix {
+    /// The rows of the matrix
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl LearnedMatrix {
+    /// Makes a matrix from its rows
+    pub fn new(rows: Vec<Vec<f64>>) -> Self {
+        LearnedMatrix { rows }
+    }
+
+    /// Takes the element-wise average of a set of matrices, such as those of the agents on a team.
+    /// This gives an error if there are no matrices or if they are not all the same shape, which
+    /// can happen with matrices loaded from a file.
+    pub fn average(matrices: &[LearnedMatrix]) -> io::Result<LearnedMatrix> {
+        let first = matrices
+            .first()
+            .ok_or_else(|| invalid_data("There are no learned matrices to average".to_string()))?;
+        if matrices
+            .iter()
+            .any(|matrix| !matrix.has_the_same_shape_as(first))
+        {
+            return Err(invalid_data(
+                "Only learned matrices of the same shape can be averaged".to_string(),
+            ));
+        }
+        let count = matrices.len() as f64;
+        Ok(LearnedMatrix::new(
+            (0..first.rows.len())
+                .map(|i| {
+                    (0..first.rows[i].len())
+                        .map(|j| matrices.iter().map(|x| x.rows[i][j]).sum::<f64>() / count)
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+
+    /// Writes a set of matrices to a file, one row per line with a blank line after each matrix
+    pub fn save_all<P: AsRef<Path>>(matrices: &[LearnedMatrix], path: P) -> io::Result<()> {
+        fs::write(
+            path,
+            matrices
+                .iter()
+                .map(|matrix| format!("{}\n", matrix))
+                .collect::<String>(),
+        )
+    }
+
+    /// Reads a set of matrices from a file written by `save_all`
+    pub fn load_all<P: AsRef<Path>>(path: P) -> io::Result<Vec<LearnedMatrix>> {
+        LearnedMatrix::parse_all(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a set of matrices, one row per line with matrices separated by blank lines
+    /// ```
+    /// use cisat::LearnedMatrix;
+    /// let contents = "0.5 0.5
+    /// 0.2 0.8
+    ///
+    /// 0.25 0.75";
+    /// let matrices = LearnedMatrix::parse_all(contents).unwrap();
+    /// assert_eq!(matrices.len(), 2);
+    /// assert_eq!(matrices[1].rows, vec![vec![0.25, 0.75]]);
+    /// ```
+    pub fn parse_all(contents: &str) -> io::Result<Vec<LearnedMatrix>> {
+        let mut matrices = vec![];
+        let mut rows = vec![];
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() {
+                if !rows.is_empty() {
+                    matrices.push(LearnedMatrix::new(std::mem::take(&mut rows)));
+                }
+            } else {
+                rows.push(
+                    line.split_whitespace()
+                        .map(parse_number)
+                        .collect::<io::Result<Vec<f64>>>()?,
+                );
+            }
+        }
+        if !rows.is_empty() {
+            matrices.push(LearnedMatrix::new(rows));
+        }
+        if matrices.is_empty() {
+            return Err(invalid_data(String::from("No learned matrices were found")));
+        }
+        Ok(matrices)
+    }
+
+    /// Checks whether two matrices have the same number of rows and columns
+    fn has_the_same_shape_as(&self, other: &LearnedMatrix) -> bool {
+        self.rows.len() == other.rows.len()
+            && self
+                .rows
+                .iter()
+                .zip(other.rows.iter())
+                .all(|(a, b)| a.len() == b.len())
+    }
+}
+
+impl fmt::Display for LearnedMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows.iter() {
+            let fields: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+            writeln!(f, "{}", fields.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod learning_tests {
+    use crate::LearnedMatrix;
+
+    #[test]
+    fn test_matrices_survive_a_round_trip() {
+        let matrices = vec![
+            LearnedMatrix::new(vec![vec![0.5, 0.5], vec![0.125, 0.875]]),
+            LearnedMatrix::new(vec![vec![0.25, 0.75], vec![0.375, 0.625]]),
+        ];
+        let contents: String = matrices.iter().map(|x| format!("{}\n", x)).collect();
+        assert_eq!(LearnedMatrix::parse_all(&contents).unwrap(), matrices);
+        assert_eq!(
+            LearnedMatrix::average(&matrices).unwrap(),
+            LearnedMatrix::new(vec![vec![0.375, 0.625], vec![0.25, 0.75]])
+        );
+        assert!(LearnedMatrix::parse_all("0.5 x").is_err());
+
+        // Matrices of different shapes can not be averaged, and neither can no matrices at all
+        let mismatched = LearnedMatrix::parse_all("0.5 0.5\n\n0.25 0.25 0.5\n").unwrap();
+        assert!(LearnedMatrix::average(&mismatched).is_err());
+        assert!(LearnedMatrix::average(&[]).is_err());
+    }
+}
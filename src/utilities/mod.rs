@@ -1,5 +1,16 @@
 //! This module contains some documentation on
 
+pub(crate) mod constraints;
+pub mod counters;
+pub use counters::Counters;
+
+pub mod learning;
+pub use learning::LearnedMatrix;
+
+pub(crate) mod normalization;
+
+pub(crate) mod parsing;
+
 pub mod parameters;
 
 pub mod randomness;
@@ -0,0 +1,63 @@
+//! This module contains the normalization of solution qualities before agents communicate
+
+use super::parameters::QualityNormalization;
+
+/// This normalizes a set of qualities as described by `QualityNormalization`. Non-finite qualities
+/// are ignored when normalizing the others, and are given a normalized quality of zero.
+pub(crate) fn normalize_qualities(
+    qualities: &[f64],
+    normalization: &QualityNormalization,
+) -> Vec<f64> {
+    let finite = || qualities.iter().copied().filter(|q| q.is_finite());
+    let normalize = |f: &dyn Fn(f64) -> f64| -> Vec<f64> {
+        qualities
+            .iter()
+            .map(|q| if q.is_finite() { f(*q) } else { 0.0 })
+            .collect()
+    };
+    match normalization {
+        QualityNormalization::MinShift => {
+            let min = finite().fold(f64::INFINITY, f64::min);
+            normalize(&|q| q - min)
+        }
+        QualityNormalization::RankBased => {
+            // Tied qualities share the average of their ranks
+            let number_of_others = (finite().count().max(2) - 1) as f64;
+            normalize(&|q| {
+                let below = finite().filter(|x| *x < q).count() as f64;
+                let tied = finite().filter(|x| *x == q).count() as f64;
+                (below + (tied - 1.0) / 2.0) / number_of_others
+            })
+        }
+        QualityNormalization::Softmax { temperature } => {
+            let max = finite().fold(f64::NEG_INFINITY, f64::max);
+            let total: f64 = finite().map(|q| ((q - max) / temperature).exp()).sum();
+            normalize(&|q| ((q - max) / temperature).exp() / total)
+        }
+        _ => normalize(&|q| q),
+    }
+}
+
+#[cfg(test)]
+mod normalization_tests {
+    use crate::{utilities::normalization::normalize_qualities, QualityNormalization};
+
+    #[test]
+    fn test_normalized_qualities_are_non_negative() {
+        let qualities = [-3.0, -1.0, f64::NAN, -1.0, -7.0];
+        assert_eq!(
+            normalize_qualities(&qualities, &QualityNormalization::MinShift),
+            vec![4.0, 6.0, 0.0, 6.0, 0.0]
+        );
+        assert_eq!(
+            normalize_qualities(&qualities, &QualityNormalization::RankBased),
+            vec![1.0 / 3.0, 2.5 / 3.0, 0.0, 2.5 / 3.0, 0.0]
+        );
+        let softmax = normalize_qualities(
+            &qualities,
+            &QualityNormalization::Softmax { temperature: 1.0 },
+        );
+        assert!((softmax.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(softmax[1] > softmax[0] && softmax[0] > softmax[4] && softmax[2] == 0.0);
+    }
+}
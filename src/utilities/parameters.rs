@@ -1,5 +1,8 @@
 //! This module contains the Parameters struct and a number of enums
-use std::fmt;
+use super::{Counters, LearnedMatrix};
+use crate::AdoptionStrategy;
+use log::warn;
+use std::{fmt, sync::Arc};
 // use strum_macros::EnumString;
 
 /// This enum carries temperature schedule options
@@ -31,6 +34,11 @@ pub enum TemperatureSchedule {
         /// How long to dwell during temperature
         dwell: usize,
     },
+    /// This keeps the temperature fixed, as the agents on a `ReplicaTeam` do
+    Fixed {
+        /// Temperature
+        temperature: f64,
+    },
     /// I guess you could just not use a temperature schedule
     None,
 }
@@ -66,6 +74,101 @@ pub enum OperationalLearning {
     None,
 }
 
+/// This enum contains options for choosing which of an agent's candidate solutions goes on to the
+/// acceptance test, when it generates more than one per iteration
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum CandidateSelection {
+    /// Take the best candidate
+    Best,
+    /// Draw a candidate with probability proportional to `exp(quality / temperature)`, at the
+    /// agent's current temperature. At zero temperature, this takes the best candidate.
+    Boltzmann,
+}
+
+/// This enum contains options for what an agent does once it has gone a number of iterations
+/// without improving on its best solution
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum StagnationResponse {
+    /// Raise the temperature to a fraction of the initial temperature and anneal from there
+    Reheat {
+        /// Fraction of the initial temperature
+        fraction: f64,
+    },
+    /// Start over from a new solution, which costs an evaluation
+    Restart,
+    /// Start over from the best solution found by the agent's team
+    RestartFromTeamBest,
+    /// Keep going as before
+    None,
+}
+
+/// This enum contains options for adapting the step size that each agent hands to its problem's
+/// move operators, which problems can use to scale their moves. The step size starts at one, and is
+/// multiplied or divided by `factor` at the end of every window of iterations.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum StepSizeAdaptation {
+    /// Grow the step size if more than a fifth of the candidates in the window improved on the
+    /// current solution, and shrink it if fewer did
+    OneFifthRule {
+        /// Number of iterations per window
+        window: usize,
+        /// Factor to grow or shrink by
+        factor: f64,
+    },
+    /// Grow the step size if more than the target fraction of the candidates in the window were
+    /// accepted, and shrink it if fewer were
+    AcceptanceRate {
+        /// Target acceptance rate
+        target: f64,
+        /// Number of iterations per window
+        window: usize,
+        /// Factor to grow or shrink by
+        factor: f64,
+    },
+    /// Keep the step size at one
+    None,
+}
+
+/// This enum contains options for the temperatures that the agents on a `ReplicaTeam` run at
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemperatureLadder {
+    /// Temperatures spaced geometrically from the lowest, for the first agent, to the highest, for
+    /// the last agent
+    Geometric {
+        /// Lowest temperature
+        lowest: f64,
+        /// Highest temperature
+        highest: f64,
+    },
+    /// Temperatures given by agent id, which should be in increasing order, and are reused from the
+    /// start of the list if there are more agents than temperatures
+    Custom {
+        /// Temperature of each agent
+        temperatures: Vec<f64>,
+    },
+}
+
+impl TemperatureLadder {
+    /// Gets the temperature of each of a number of agents
+    pub fn get_temperatures(&self, number_of_agents: usize) -> Vec<f64> {
+        match self {
+            TemperatureLadder::Geometric { lowest, highest } => {
+                let ratio = (highest / lowest).powf(1.0 / (number_of_agents.max(2) - 1) as f64);
+                (0..number_of_agents)
+                    .map(|i| lowest * ratio.powi(i as i32))
+                    .collect()
+            }
+            TemperatureLadder::Custom { temperatures } => (0..number_of_agents)
+                .map(|i| temperatures[i % temperatures.len()])
+                .collect(),
+        }
+    }
+}
+
 /// This enum contains options for agent interaction
 #[non_exhaustive]
 #[derive(Clone, Debug)]
@@ -89,6 +192,149 @@ pub enum CommunicationStyle {
     None,
 }
 
+/// This enum contains options for handling solutions that violate constraints
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstraintHandling {
+    /// Subtract a fixed multiple of the constraint violation from the quality
+    StaticPenalty {
+        /// Penalty coefficient
+        penalty: f64,
+    },
+    /// Subtract a multiple of the constraint violation from the quality, where the multiple is
+    /// relaxed when the agent's recent solutions have all been feasible, and raised when they have
+    /// all been infeasible
+    AdaptivePenalty {
+        /// Initial penalty coefficient
+        initial_penalty: f64,
+        /// Factor by which the penalty is divided after a window of feasible solutions
+        decrease_factor: f64,
+        /// Factor by which the penalty is multiplied after a window of infeasible solutions
+        increase_factor: f64,
+        /// Number of iterations considered when adapting the penalty
+        window: usize,
+    },
+    /// Feasible solutions beat infeasible ones, feasible solutions are compared by quality and
+    /// infeasible ones by violation (Deb's rules)
+    FeasibilityFirst,
+    /// Solutions are compared by quality with some probability, and otherwise by violation, unless
+    /// both are feasible (Runarsson and Yao)
+    StochasticRanking {
+        /// Probability of comparing infeasible solutions by quality
+        probability: f64,
+    },
+    /// Constraint violations are ignored
+    None,
+}
+
+/// This enum contains options for normalizing solution qualities before agents choose which
+/// solution to adopt during communication. When an agent communicates, each solution on the team
+/// is weighted by
+///
+/// `weight = max(0, normalized quality + quality_bias + self_bias if the solution is the agent's own)`
+///
+/// and one solution is drawn with probability proportional to its weight. A larger quality bias
+/// flattens the distribution, reducing the preference for better solutions, while the self bias
+/// makes agents more likely to keep their own solution. Solutions with non-finite quality have no
+/// weight, and if no solution has any weight the agent keeps its own.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum QualityNormalization {
+    /// Shift qualities so that the worst solution has a normalized quality of zero
+    MinShift,
+    /// Use the rank of each quality, scaled from zero for the worst solution to one for the best
+    RankBased,
+    /// Use the softmax of the qualities, which sums to one over the team
+    Softmax {
+        /// Softmax temperature, where lower values favor the best solution more strongly
+        temperature: f64,
+    },
+    /// Use raw qualities, which only makes sense for problems with non-negative qualities. Most
+    /// benchmarks have negative qualities, which leave every weight at zero, so agents never adopt.
+    None,
+}
+
+/// This enum contains options for how agents decide which solution to adopt when they communicate
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum AdoptionStyle {
+    /// Draw a solution with probability proportional to its weight, as described by
+    /// `QualityNormalization`. This is the only style that uses the self and quality biases.
+    WeightedDraw,
+    /// Adopt the best solution on the team, keeping our own if it is among the best
+    GreedyBest,
+    /// Adopt the best of a number of solutions drawn uniformly at random, with replacement
+    Tournament {
+        /// Number of solutions drawn
+        size: usize,
+    },
+    /// Draw a solution with probability proportional to the exponential of its quality divided by
+    /// a temperature
+    Boltzmann {
+        /// Temperature, where lower values favor the best solution more strongly
+        temperature: f64,
+    },
+    /// Make a weighted draw, but only adopt the solution drawn if it is better than our own
+    OnlyIfBetter,
+    /// Adopt the solution of a teammate drawn uniformly at random, regardless of quality
+    RandomPeer,
+    /// Use some other rule
+    Custom(Arc<dyn AdoptionStrategy>),
+}
+
+/// This enum contains options for what a communication event costs each participant
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum MeetingCost {
+    /// Agents spend a number of iterations in the meeting, including the one in which it happens,
+    /// during which they do not work on their solutions
+    Iterations {
+        /// Number of iterations
+        iterations: usize,
+    },
+    /// Agents are charged a number of evaluations for the meeting, which count against the
+    /// evaluation budget
+    Evaluations {
+        /// Number of evaluations
+        evaluations: usize,
+    },
+    /// Meetings are free
+    None,
+}
+
+/// This enum contains options for how quickly agents work, which is used by teams that simulate
+/// time, such as `EventTeam`. Rates are given in moves per unit of time for each agent, and are
+/// reused from the start of the list if there are more agents than rates.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum WorkPace {
+    /// Each agent takes the same time for every move
+    Constant {
+        /// Rate of each agent
+        rates: Vec<f64>,
+    },
+    /// The time each agent takes for a move is drawn from an exponential distribution
+    Exponential {
+        /// Mean rate of each agent
+        rates: Vec<f64>,
+    },
+    /// Every agent makes one move per unit of time
+    None,
+}
+
+/// This enum contains the kinds of agent that can be put on a `MixedTeam`
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AgentKind {
+    /// A CISAT agent, which anneals with the temperature schedule and operational learning given
+    /// by the parameters
+    Annealing,
+    /// A greedy hill-climbing agent
+    HillClimbing,
+    /// A random search agent
+    RandomSearch,
+}
+
 /// This parameters struct. This tells CISAT what to do
 #[derive(Clone, Debug)]
 pub struct Parameters {
@@ -102,14 +348,69 @@ pub struct Parameters {
     pub temperature_schedule: TemperatureSchedule,
     /// The operational learning style to use
     pub operational_learning: OperationalLearning,
+    /// The number of candidate solutions each agent generates per iteration, which is treated as 1
+    /// if it is 0
+    pub number_of_candidates: usize,
+    /// How agents choose among their candidate solutions
+    pub candidate_selection: CandidateSelection,
+    /// Whether each of an agent's candidates in an iteration comes from a different move
+    /// operator, as far as there are enough of them
+    pub mix_move_operators: bool,
+    /// How agents adapt the step size of their moves
+    pub step_size_adaptation: StepSizeAdaptation,
+    /// What agents do when they stagnate
+    pub stagnation_response: StagnationResponse,
+    /// The number of iterations without improvement after which an agent is stagnating
+    pub stagnation_iterations: usize,
     /// The communication style to use
     pub communication: CommunicationStyle,
-    /// The self bias value to use
+    /// What each communication event costs its participants
+    pub meeting_cost: MeetingCost,
+    /// The weight added to an agent's own solution when choosing which solution to adopt
     pub self_bias: f64,
-    /// The quality bias value to use
+    /// The weight added to every solution when choosing which solution to adopt, which reduces the
+    /// bias toward better solutions. Without quality normalization, solutions whose quality is
+    /// below minus the quality bias get no weight, so agents on problems with negative qualities
+    /// never adopt unless the bias is large enough.
     pub quality_bias: f64,
+    /// How qualities are normalized when choosing which solution to adopt
+    pub quality_normalization: QualityNormalization,
+    /// How agents decide which solution to adopt
+    pub adoption: AdoptionStyle,
+    /// The probability that an agent recombines a teammate's solution with its own, rather than
+    /// adopting it wholesale, for problems that support crossover, such as Ackley (but not
+    /// Structure)
+    pub crossover_rate: f64,
     /// The satisficing fraction to use
     pub satisficing_fraction: f64,
+    /// The constraint handling style to use
+    pub constraint_handling: ConstraintHandling,
+    /// The maximum number of evaluations each team may use, if any. The budget is checked before
+    /// each team iteration, and an iteration that has started is always finished, so a team may
+    /// overshoot the budget by what its last iteration costs. On a `Team`, each agent uses at most
+    /// one evaluation per candidate in an iteration, plus one if it restarts from a new solution
+    /// and any evaluations charged for a meeting. An `EventTeam` iteration is a unit of time, in
+    /// which fast agents may move several times.
+    pub evaluation_budget: Option<usize>,
+    /// The seed for random number generation, if any. Runs with the same seed give the same
+    /// results, whether or not agents are iterated in parallel.
+    pub seed: Option<u64>,
+    /// Whether agents within a team are iterated in parallel between communication events
+    pub parallel_agents: bool,
+    /// Whether the teams in a cohort are iterated in parallel when it is stepped through one
+    /// iteration at a time
+    pub parallel_teams: bool,
+    /// The number of threads a cohort runs its teams on, or `None` to use rayon's global pool
+    pub number_of_threads: Option<usize>,
+    /// How quickly each agent works, for teams that simulate time
+    pub work_pace: WorkPace,
+    /// The temperatures of the agents on a `ReplicaTeam`
+    pub temperature_ladder: TemperatureLadder,
+    /// The number of iterations between replica exchanges on a `ReplicaTeam`
+    pub exchange_interval: usize,
+    /// The kind of each agent on a `MixedTeam`, reused from the start of the list if there are more
+    /// agents than kinds. Every agent is an annealing agent if the list is empty.
+    pub agent_kinds: Vec<AgentKind>,
 }
 
 impl Parameters {
@@ -122,6 +423,132 @@ impl Parameters {
         if self.satisficing_fraction < 0.0 || self.satisficing_fraction > 1.0 {
             panic!("The satisficing fraction must be between 0 and 1 inclusive.");
         }
+        match &self.operational_learning {
+            OperationalLearning::Multinomial {
+                learning_rate,
+                initial_learning_matrix,
+            } => {
+                if !(0.0..=1.0).contains(learning_rate) {
+                    panic!("The learning rate must be between 0 and 1 inclusive.");
+                }
+                if initial_learning_matrix.iter().any(|x| *x < 0.0) {
+                    panic!("The initial learning matrix must not be negative.");
+                }
+            }
+            OperationalLearning::Markov {
+                learning_rate,
+                initial_learning_matrix,
+            } => {
+                if !(0.0..=1.0).contains(learning_rate) {
+                    panic!("The learning rate must be between 0 and 1 inclusive.");
+                }
+                if initial_learning_matrix.iter().flatten().any(|x| *x < 0.0) {
+                    panic!("The initial learning matrix must not be negative.");
+                }
+            }
+            _ => {}
+        }
+        match self.constraint_handling {
+            ConstraintHandling::AdaptivePenalty {
+                decrease_factor,
+                increase_factor,
+                window,
+                ..
+            } => {
+                if decrease_factor < 1.0 || increase_factor < 1.0 {
+                    panic!("The adaptive penalty factors must be at least 1.");
+                }
+                if window == 0 {
+                    panic!("The adaptive penalty window must be at least 1.");
+                }
+            }
+            ConstraintHandling::StochasticRanking { probability }
+                if !(0.0..=1.0).contains(&probability) =>
+            {
+                panic!("The stochastic ranking probability must be between 0 and 1 inclusive.");
+            }
+            _ => {}
+        }
+        match self.communication {
+            CommunicationStyle::ConstantFrequency { frequency } if frequency < 0.0 => {
+                panic!("The communication frequency must not be negative.");
+            }
+            CommunicationStyle::RegularInterval { interval: 0 } => {
+                panic!("The communication interval must be at least 1.");
+            }
+            _ => {}
+        }
+        match self.step_size_adaptation {
+            StepSizeAdaptation::OneFifthRule { window, factor }
+            | StepSizeAdaptation::AcceptanceRate { window, factor, .. }
+                if window == 0 || factor <= 1.0 =>
+            {
+                panic!(
+                    "Step sizes must be adapted over at least one iteration, by a factor above 1."
+                );
+            }
+            StepSizeAdaptation::AcceptanceRate { target, .. } if target <= 0.0 || target >= 1.0 => {
+                panic!("The target acceptance rate must be between 0 and 1 exclusive.");
+            }
+            _ => {}
+        }
+        match self.stagnation_response {
+            StagnationResponse::Reheat { fraction }
+                if fraction.is_nan() || fraction <= 0.0 || fraction > 1.0 =>
+            {
+                panic!("The reheating fraction must be greater than 0 and at most 1.");
+            }
+            StagnationResponse::None => {}
+            _ if self.stagnation_iterations == 0 => {
+                panic!("Agents must be given at least one iteration before they stagnate.");
+            }
+            _ => {}
+        }
+        match &self.temperature_ladder {
+            TemperatureLadder::Geometric { lowest, highest }
+                if *lowest <= 0.0 || *highest < *lowest =>
+            {
+                panic!("The temperature ladder must run from a positive lowest temperature up to the highest.");
+            }
+            TemperatureLadder::Custom { temperatures }
+                if temperatures.is_empty() || temperatures.iter().any(|t| *t <= 0.0) =>
+            {
+                panic!("The temperatures on the ladder must be positive, and there must be at least one.");
+            }
+            _ => {}
+        }
+        if self.exchange_interval == 0 {
+            panic!("The exchange interval must be at least 1.");
+        }
+        if self.number_of_threads == Some(0) {
+            panic!("The number of threads must be at least 1.");
+        }
+        if self.crossover_rate < 0.0 || self.crossover_rate > 1.0 {
+            panic!("The crossover rate must be between 0 and 1 inclusive.");
+        }
+        match self.adoption {
+            AdoptionStyle::Tournament { size: 0 } => {
+                panic!("The tournament size must be at least 1.");
+            }
+            AdoptionStyle::Boltzmann { temperature } if temperature <= 0.0 => {
+                panic!("The Boltzmann temperature must be positive.");
+            }
+            _ => {}
+        }
+        match &self.work_pace {
+            WorkPace::Constant { rates } | WorkPace::Exponential { rates }
+                if rates.is_empty()
+                    || rates.iter().any(|rate| !rate.is_finite() || *rate <= 0.0) =>
+            {
+                panic!("Work rates must be positive and finite, and there must be at least one.");
+            }
+            _ => {}
+        }
+        if let QualityNormalization::Softmax { temperature } = self.quality_normalization {
+            if temperature <= 0.0 {
+                panic!("The softmax temperature must be positive.");
+            }
+        }
     }
     /// Returns values necessary to run HSAT
     pub fn hsat() -> Self {
@@ -153,6 +580,137 @@ impl Parameters {
         self.number_of_iterations = number_of_iterations;
         self
     }
+    /// Set quality normalization style
+    pub fn with_quality_normalization(
+        mut self,
+        quality_normalization: QualityNormalization,
+    ) -> Self {
+        self.quality_normalization = quality_normalization;
+        self
+    }
+    /// Set number of candidates
+    pub fn with_candidates(mut self, number_of_candidates: usize) -> Self {
+        self.number_of_candidates = number_of_candidates;
+        self
+    }
+    /// Set candidate selection
+    pub fn with_candidate_selection(mut self, candidate_selection: CandidateSelection) -> Self {
+        self.candidate_selection = candidate_selection;
+        self
+    }
+    /// Set step size adaptation
+    pub fn with_step_size_adaptation(mut self, step_size_adaptation: StepSizeAdaptation) -> Self {
+        self.step_size_adaptation = step_size_adaptation;
+        self
+    }
+    /// Set the response to stagnation, and the number of iterations without improvement that
+    /// triggers it
+    pub fn with_stagnation_response(
+        mut self,
+        stagnation_response: StagnationResponse,
+        stagnation_iterations: usize,
+    ) -> Self {
+        self.stagnation_response = stagnation_response;
+        self.stagnation_iterations = stagnation_iterations;
+        self
+    }
+    /// Set meeting cost
+    pub fn with_meeting_cost(mut self, meeting_cost: MeetingCost) -> Self {
+        self.meeting_cost = meeting_cost;
+        self
+    }
+    /// Set adoption style
+    pub fn with_adoption(mut self, adoption: AdoptionStyle) -> Self {
+        self.adoption = adoption;
+        self
+    }
+    /// Set crossover rate
+    pub fn with_crossover_rate(mut self, crossover_rate: f64) -> Self {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+    /// Set constraint handling style
+    pub fn with_constraint_handling(mut self, constraint_handling: ConstraintHandling) -> Self {
+        self.constraint_handling = constraint_handling;
+        self
+    }
+    /// Set evaluation budget
+    pub fn with_evaluation_budget(mut self, evaluation_budget: usize) -> Self {
+        self.evaluation_budget = Some(evaluation_budget);
+        self
+    }
+    /// Set random seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    /// Set whether agents are iterated in parallel
+    pub fn with_parallel_agents(mut self, parallel_agents: bool) -> Self {
+        self.parallel_agents = parallel_agents;
+        self
+    }
+    /// Set whether teams are iterated in parallel
+    pub fn with_parallel_teams(mut self, parallel_teams: bool) -> Self {
+        self.parallel_teams = parallel_teams;
+        self
+    }
+    /// Set number of threads
+    pub fn with_number_of_threads(mut self, number_of_threads: usize) -> Self {
+        self.number_of_threads = Some(number_of_threads);
+        self
+    }
+    /// Set the initial learning matrix of the operational learning style from a learned matrix,
+    /// such as one saved from an earlier run. Multinomial learning takes its first row. Other
+    /// learning styles have no learning matrix, so the matrix is ignored with a warning.
+    pub fn with_learned_matrix(mut self, learned_matrix: LearnedMatrix) -> Self {
+        match &mut self.operational_learning {
+            OperationalLearning::Multinomial {
+                initial_learning_matrix,
+                ..
+            } => {
+                *initial_learning_matrix =
+                    learned_matrix.rows.into_iter().next().unwrap_or_default();
+            }
+            OperationalLearning::Markov {
+                initial_learning_matrix,
+                ..
+            } => {
+                *initial_learning_matrix = learned_matrix.rows;
+            }
+            _ => warn!(
+                "The learned matrix was ignored, because the operational learning style has no \
+                 learning matrix to start from"
+            ),
+        }
+        self
+    }
+    /// Set work pace
+    pub fn with_work_pace(mut self, work_pace: WorkPace) -> Self {
+        self.work_pace = work_pace;
+        self
+    }
+    /// Set temperature ladder
+    pub fn with_temperature_ladder(mut self, temperature_ladder: TemperatureLadder) -> Self {
+        self.temperature_ladder = temperature_ladder;
+        self
+    }
+    /// Set exchange interval
+    pub fn with_exchange_interval(mut self, exchange_interval: usize) -> Self {
+        self.exchange_interval = exchange_interval;
+        self
+    }
+    /// Set agent kinds
+    pub fn with_agent_kinds(mut self, agent_kinds: Vec<AgentKind>) -> Self {
+        self.agent_kinds = agent_kinds;
+        self
+    }
+    /// Checks whether a set of counters has used up the evaluation budget
+    pub fn is_budget_exhausted(&self, counters: &Counters) -> bool {
+        match self.evaluation_budget {
+            Some(budget) => counters.evaluations >= budget,
+            None => false,
+        }
+    }
 }
 
 impl Default for Parameters {
@@ -166,10 +724,33 @@ impl Default for Parameters {
                 dwell: 1,
             },
             operational_learning: OperationalLearning::None,
+            number_of_candidates: 1,
+            candidate_selection: CandidateSelection::Best,
+            mix_move_operators: false,
+            step_size_adaptation: StepSizeAdaptation::None,
+            stagnation_response: StagnationResponse::None,
+            stagnation_iterations: 20,
             communication: CommunicationStyle::None,
+            meeting_cost: MeetingCost::None,
             self_bias: 1.0,
             quality_bias: 1.0,
+            quality_normalization: QualityNormalization::MinShift,
+            adoption: AdoptionStyle::WeightedDraw,
+            crossover_rate: 0.0,
             satisficing_fraction: 0.5,
+            constraint_handling: ConstraintHandling::None,
+            evaluation_budget: None,
+            seed: None,
+            parallel_agents: false,
+            parallel_teams: false,
+            number_of_threads: None,
+            work_pace: WorkPace::None,
+            temperature_ladder: TemperatureLadder::Geometric {
+                lowest: 0.01,
+                highest: 1.0,
+            },
+            exchange_interval: 10,
+            agent_kinds: vec![],
         }
     }
 }
@@ -210,6 +791,10 @@ impl fmt::Display for Parameters {
                 writeln!(f, "    - initial_temperature = {}", initial_temperature);
                 writeln!(f, "    - dwell = {}", dwell);
             }
+            TemperatureSchedule::Fixed { temperature } => {
+                writeln!(f, " - Fixed temperature");
+                writeln!(f, "    - temperature = {}", temperature);
+            }
             TemperatureSchedule::None => {
                 writeln!(f, " - No annealing schedule");
             }
@@ -237,9 +822,188 @@ impl fmt::Display for Parameters {
                 writeln!(f, " - No operational learning");
             }
         }
+        if self.number_of_candidates > 1 {
+            writeln!(f, " - number of candidates = {}", self.number_of_candidates);
+            match self.candidate_selection {
+                CandidateSelection::Best => {
+                    writeln!(f, "    - best candidate taken");
+                }
+                CandidateSelection::Boltzmann => {
+                    writeln!(f, "    - Boltzmann candidate selection");
+                }
+            }
+            if self.mix_move_operators {
+                writeln!(f, "    - move operators mixed");
+            }
+        }
+        match self.step_size_adaptation {
+            StepSizeAdaptation::OneFifthRule { window, factor } => {
+                writeln!(f, " - step sizes adapted by the one-fifth success rule");
+                writeln!(f, "    - window = {}", window);
+                writeln!(f, "    - factor = {}", factor);
+            }
+            StepSizeAdaptation::AcceptanceRate {
+                target,
+                window,
+                factor,
+            } => {
+                writeln!(
+                    f,
+                    " - step sizes adapted to an acceptance rate of {}",
+                    target
+                );
+                writeln!(f, "    - window = {}", window);
+                writeln!(f, "    - factor = {}", factor);
+            }
+            StepSizeAdaptation::None => {}
+        }
+        match self.stagnation_response {
+            StagnationResponse::Reheat { fraction } => {
+                writeln!(
+                    f,
+                    " - reheat to {} of the initial temperature after {} iterations without improvement",
+                    fraction, self.stagnation_iterations
+                );
+            }
+            StagnationResponse::Restart => {
+                writeln!(
+                    f,
+                    " - restart after {} iterations without improvement",
+                    self.stagnation_iterations
+                );
+            }
+            StagnationResponse::RestartFromTeamBest => {
+                writeln!(
+                    f,
+                    " - restart from the team best after {} iterations without improvement",
+                    self.stagnation_iterations
+                );
+            }
+            StagnationResponse::None => {}
+        }
+        match self.meeting_cost {
+            MeetingCost::Iterations { iterations } => {
+                writeln!(f, " - meetings cost {} iterations", iterations);
+            }
+            MeetingCost::Evaluations { evaluations } => {
+                writeln!(f, " - meetings cost {} evaluations", evaluations);
+            }
+            MeetingCost::None => {}
+        }
         writeln!(f, " - self bias = {}", self.self_bias);
         writeln!(f, " - quality bias = {}", self.quality_bias);
+        match self.quality_normalization {
+            QualityNormalization::MinShift => {
+                writeln!(f, " - Min-shift quality normalization");
+            }
+            QualityNormalization::RankBased => {
+                writeln!(f, " - Rank-based quality normalization");
+            }
+            QualityNormalization::Softmax { temperature } => {
+                writeln!(f, " - Softmax quality normalization");
+                writeln!(f, "    - temperature = {}", temperature);
+            }
+            QualityNormalization::None => {}
+        }
+        match &self.adoption {
+            AdoptionStyle::WeightedDraw => {}
+            AdoptionStyle::GreedyBest => {
+                writeln!(f, " - Greedy adoption");
+            }
+            AdoptionStyle::Tournament { size } => {
+                writeln!(f, " - Tournament adoption");
+                writeln!(f, "    - size = {}", size);
+            }
+            AdoptionStyle::Boltzmann { temperature } => {
+                writeln!(f, " - Boltzmann adoption");
+                writeln!(f, "    - temperature = {}", temperature);
+            }
+            AdoptionStyle::OnlyIfBetter => {
+                writeln!(f, " - Adoption only of better solutions");
+            }
+            AdoptionStyle::RandomPeer => {
+                writeln!(f, " - Random peer adoption");
+            }
+            AdoptionStyle::Custom(strategy) => {
+                writeln!(f, " - Custom adoption: {:?}", strategy);
+            }
+        }
         writeln!(f, " - satisficing fraction = {}", self.satisficing_fraction);
+        if self.crossover_rate > 0.0 {
+            writeln!(f, " - crossover rate = {}", self.crossover_rate);
+        }
+        match self.constraint_handling {
+            ConstraintHandling::StaticPenalty { penalty } => {
+                writeln!(f, " - Static penalty constraint handling");
+                writeln!(f, "    - penalty = {}", penalty);
+            }
+            ConstraintHandling::AdaptivePenalty {
+                initial_penalty,
+                decrease_factor,
+                increase_factor,
+                window,
+            } => {
+                writeln!(f, " - Adaptive penalty constraint handling");
+                writeln!(f, "    - initial penalty = {}", initial_penalty);
+                writeln!(f, "    - decrease factor = {}", decrease_factor);
+                writeln!(f, "    - increase factor = {}", increase_factor);
+                writeln!(f, "    - window = {}", window);
+            }
+            ConstraintHandling::FeasibilityFirst => {
+                writeln!(f, " - Feasibility-first constraint handling");
+            }
+            ConstraintHandling::StochasticRanking { probability } => {
+                writeln!(f, " - Stochastic ranking constraint handling");
+                writeln!(f, "    - probability = {}", probability);
+            }
+            ConstraintHandling::None => {}
+        }
+        if let Some(budget) = self.evaluation_budget {
+            writeln!(f, " - evaluation budget = {}", budget);
+        }
+        if let Some(seed) = self.seed {
+            writeln!(f, " - seed = {}", seed);
+        }
+        if self.parallel_agents {
+            writeln!(f, " - agents iterated in parallel");
+        }
+        if self.parallel_teams {
+            writeln!(f, " - teams iterated in parallel");
+        }
+        if let Some(number_of_threads) = self.number_of_threads {
+            writeln!(f, " - number of threads = {}", number_of_threads);
+        }
+        match &self.work_pace {
+            WorkPace::Constant { rates } => {
+                writeln!(f, " - constant work rates = {:?}", rates);
+            }
+            WorkPace::Exponential { rates } => {
+                writeln!(f, " - exponential work rates = {:?}", rates);
+            }
+            WorkPace::None => {}
+        }
+        // The replica exchange settings are only shown if they have been changed
+        let default = Parameters::default();
+        if self.temperature_ladder != default.temperature_ladder {
+            match &self.temperature_ladder {
+                TemperatureLadder::Geometric { lowest, highest } => {
+                    writeln!(
+                        f,
+                        " - geometric temperature ladder from {} to {}",
+                        lowest, highest
+                    );
+                }
+                TemperatureLadder::Custom { temperatures } => {
+                    writeln!(f, " - temperature ladder = {:?}", temperatures);
+                }
+            }
+        }
+        if self.exchange_interval != default.exchange_interval {
+            writeln!(f, " - exchange interval = {}", self.exchange_interval);
+        }
+        if !self.agent_kinds.is_empty() {
+            writeln!(f, " - agent kinds = {:?}", self.agent_kinds);
+        }
         Ok(())
     }
 }
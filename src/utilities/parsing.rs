@@ -0,0 +1,44 @@
+//! This module contains helpers for reading numbers from files, such as problem instances and
+//! learned matrices
+
+use std::{io, str::FromStr};
+
+/// Makes an error for a malformed file
+pub(crate) fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Parses a number from a file
+pub(crate) fn parse_number<T: FromStr>(field: &str) -> io::Result<T> {
+    field
+        .parse()
+        .map_err(|_| invalid_data(format!("Could not parse {} as a number", field)))
+}
+
+/// This reads the whitespace-separated numbers that OR-Library instance files are made of
+pub(crate) struct NumberReader<'a> {
+    /// The remaining fields of the file
+    fields: std::str::SplitWhitespace<'a>,
+}
+
+impl<'a> NumberReader<'a> {
+    /// Makes a reader over the contents of a file
+    pub(crate) fn new(contents: &'a str) -> Self {
+        NumberReader {
+            fields: contents.split_whitespace(),
+        }
+    }
+
+    /// Reads the next number
+    pub(crate) fn next<T: FromStr>(&mut self) -> io::Result<T> {
+        match self.fields.next() {
+            Some(field) => parse_number(field),
+            None => Err(invalid_data(String::from("Unexpected end of file"))),
+        }
+    }
+
+    /// Reads the next `count` numbers
+    pub(crate) fn next_n<T: FromStr>(&mut self, count: usize) -> io::Result<Vec<T>> {
+        (0..count).map(|_| self.next()).collect()
+    }
+}
@@ -1,7 +1,43 @@
 //! This contains some randomization functions that are useful
 
-use rand::thread_rng;
-use rand_distr::{Distribution, Normal, Uniform, WeightedIndex};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rand_distr::{Distribution, Exp, Normal, Uniform, WeightedIndex};
+use std::cell::RefCell;
+
+thread_local! {
+    /// The random number generator that all draws on the current thread come from
+    static THREAD_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// This makes a random number generator from a seed, or from entropy if there is no seed
+pub(crate) fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// This makes every draw on the current thread come from `rng` until the returned guard is
+/// dropped. Solutions draw from the thread's random number generator, so agents and teams lend it
+/// their own around calls into a solution, which keeps parallel runs deterministic under a fixed
+/// seed no matter which thread the work runs on.
+pub(crate) fn lend_rng(rng: &mut StdRng) -> LentRng<'_> {
+    THREAD_RNG.with(|thread_rng| std::mem::swap(&mut *thread_rng.borrow_mut(), rng));
+    LentRng { rng }
+}
+
+/// This is a random number generator lent to the current thread by `lend_rng`. It is handed back
+/// when this is dropped, even if the work it was lent for panics or returns early.
+pub(crate) struct LentRng<'a> {
+    /// Where the thread's own random number generator is kept in the meantime
+    rng: &'a mut StdRng,
+}
+
+impl Drop for LentRng<'_> {
+    fn drop(&mut self) {
+        THREAD_RNG.with(|thread_rng| std::mem::swap(&mut *thread_rng.borrow_mut(), self.rng));
+    }
+}
 
 /// This is a function for generating a random vector from a Gaussian distribution
 pub(crate) fn random_gaussian_vector(
@@ -13,22 +49,21 @@ pub(crate) fn random_gaussian_vector(
     let mut random_vector = vec![0.0; length];
 
     // Make a distribution to upll from
-    let mut rng = thread_rng();
     let normal = Normal::new(mean, standard_deviation).unwrap();
 
     // Replace elements with uniform normals
-    for elem in random_vector.iter_mut().take(length) {
-        *elem += normal.sample(&mut rng);
-    }
+    THREAD_RNG.with(|rng| {
+        for elem in random_vector.iter_mut().take(length) {
+            *elem += normal.sample(&mut *rng.borrow_mut());
+        }
+    });
 
     random_vector
 }
 
 /// This make a multinomial draw from a set of weights - think a loaded die
-pub(crate) fn multinomial_draw(weights: Vec<f64>) -> usize {
-    let mut rng = thread_rng();
-    let weighted = WeightedIndex::new(weights).unwrap();
-    weighted.sample(&mut rng)
+pub(crate) fn multinomial_draw<R: Rng + ?Sized>(weights: Vec<f64>, rng: &mut R) -> usize {
+    WeightedIndex::new(weights).unwrap().sample(rng)
 }
 
 /// This make a multinomial draw from a set of weights with identifiers - think a loaded die with weird names for the faces
@@ -41,12 +76,9 @@ pub(crate) fn multinomial_tuple_draw<T: Copy>(identifiers_and_weights: Vec<(T, f
         identifiers.push(elem.0);
     }
 
-    // Make rng thread
-    let mut rng = thread_rng();
-
     // Perform weighted draw and get index
     let weighted = WeightedIndex::new(weights).unwrap();
-    let idx = weighted.sample(&mut rng);
+    let idx = THREAD_RNG.with(|rng| weighted.sample(&mut *rng.borrow_mut()));
     identifiers[idx]
 }
 
@@ -56,29 +88,48 @@ pub(crate) fn random_uniform_vector(length: usize, low: f64, high: f64) -> Vec<f
     let mut random_vector = vec![0.0; length];
 
     // Make a distribution to upll from
-    let mut rng = thread_rng();
     let uniform = Uniform::new_inclusive(low, high);
 
     // Replace elemnts with uniform normals
-    for elem in random_vector.iter_mut().take(length) {
-        *elem += uniform.sample(&mut rng);
-    }
+    THREAD_RNG.with(|rng| {
+        for elem in random_vector.iter_mut().take(length) {
+            *elem += uniform.sample(&mut *rng.borrow_mut());
+        }
+    });
 
     random_vector
 }
 
+/// Random index into a collection of the given length
+pub(crate) fn random_index(length: usize) -> usize {
+    let uniform = Uniform::new(0, length);
+    THREAD_RNG.with(|rng| uniform.sample(&mut *rng.borrow_mut()))
+}
+
+/// Random permutation of the numbers from 0 up to, but not including, the given length
+pub(crate) fn random_permutation(length: usize) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..length).collect();
+    THREAD_RNG.with(|rng| permutation.shuffle(&mut *rng.borrow_mut()));
+    permutation
+}
+
 /// Random number between 0 and 1
 pub(crate) fn random_unit_draw() -> f64 {
     // Make a distribution to upll from
-    let mut rng = thread_rng();
     let uniform = Uniform::new_inclusive(0.0, 1.0);
-    uniform.sample(&mut rng)
+    THREAD_RNG.with(|rng| uniform.sample(&mut *rng.borrow_mut()))
+}
+
+/// Random waiting time until the next event of a process with the given rate
+pub(crate) fn random_exponential<R: Rng + ?Sized>(rate: f64, rng: &mut R) -> f64 {
+    Exp::new(rate).unwrap().sample(rng)
 }
 
 #[cfg(test)]
 mod random_tests {
     use crate::utilities::randomness::{
-        multinomial_draw, random_gaussian_vector, random_uniform_vector,
+        lend_rng, multinomial_draw, random_gaussian_vector, random_uniform_vector,
+        random_unit_draw, seeded_rng,
     };
 
     #[test]
@@ -95,7 +146,26 @@ mod random_tests {
 
     #[test]
     fn test_multinomial_draw() {
-        let x = multinomial_draw(vec![1.0, 2.3, 10.1, 3.1]);
+        let x = multinomial_draw(vec![1.0, 2.3, 10.1, 3.1], &mut seeded_rng(None));
         println!("mult: {:?}", x);
     }
+
+    #[test]
+    fn test_lent_rng_is_handed_back_after_a_panic() {
+        let expected = {
+            let mut rng = seeded_rng(Some(1));
+            let _rng = lend_rng(&mut rng);
+            random_unit_draw()
+        };
+
+        let mut rng = seeded_rng(Some(1));
+        let _rng = lend_rng(&mut rng);
+        let result = std::panic::catch_unwind(|| {
+            let mut other = seeded_rng(Some(2));
+            let _other = lend_rng(&mut other);
+            panic!("The work failed");
+        });
+        assert!(result.is_err());
+        assert_eq!(random_unit_draw(), expected);
+    }
 }
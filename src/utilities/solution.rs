@@ -1,19 +1,57 @@
 //! This module contains the Solution trait, which can be used to implement new Solution types
 
+use std::cmp::Ordering;
 use std::fmt::Debug;
-use std::ops::Sub;
 
 /// This trait is the Solution trait, which provides the necessary pieces for a problem to
-/// interface with CISAT
-pub trait Solution: PartialOrd + Sub<Output = f64> + Sized + Send + Ord + Clone + Debug {
+/// interface with CISAT. The comparison impls it requires can be generated with
+/// `#[derive(CisatSolution)]`, which orders solutions by the field marked `#[quality]`. Solutions
+/// must be `Sync`, because agents share them with their team rather than copying them.
+pub trait Solution: PartialOrd + Sized + Send + Sync + Ord + Clone + Debug {
     /// A problem must have a number of move operators specified
     const NUMBER_OF_MOVE_OPERATORS: usize;
     /// A problem must have a number of objectives specified
     const NUMBER_OF_OBJECTIVES: usize;
+    /// A problem whose moves are proportional to the temperature may set this, so that the step
+    /// size scales its moves by scaling the temperature
+    const STEP_SIZE_SCALES_TEMPERATURE: bool = false;
     /// A problem must have a means for generating an new solution
     fn new() -> Self;
-    /// A problem must have a way to apply move operators to itself
+    /// A problem must have a way to apply move operators to itself. Agents pass in their current
+    /// temperature, or one if they have no temperature schedule.
     fn apply_move_operator(&mut self, move_index: usize, temperature: f64);
+    /// A problem may scale its moves by a step size that agents adapt as they search, where a step
+    /// size of one leaves moves as they are. By default, the step size multiplies the temperature
+    /// if `STEP_SIZE_SCALES_TEMPERATURE` is set, and is ignored otherwise.
+    fn apply_scaled_move_operator(&mut self, move_index: usize, temperature: f64, step_size: f64) {
+        if Self::STEP_SIZE_SCALES_TEMPERATURE {
+            self.apply_move_operator(move_index, temperature * step_size);
+        } else {
+            self.apply_move_operator(move_index, temperature);
+        }
+    }
     /// A problem must have a mapping to a quality scalar
     fn get_quality_scalar(&self) -> f64;
+    /// A problem may report how far it is from satisfying its constraints, where zero means that
+    /// it is feasible. Problems are unconstrained unless they say otherwise.
+    fn get_constraint_violation(&self) -> f64 {
+        0.0
+    }
+    /// A problem may combine parts of another solution with its own, returning a new, evaluated
+    /// solution. Agents use this to merge a teammate's ideas into their own when they communicate.
+    /// Problems that return `None`, as they do by default, can only be adopted wholesale.
+    fn crossover(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+}
+
+/// Compares two quality scalars, treating non-finite qualities as worse than any finite quality
+/// and equal to each other. This matches the ordering generated by `#[derive(CisatSolution)]`.
+pub(crate) fn compare_qualities(a: f64, b: f64) -> Ordering {
+    match (a.is_finite(), b.is_finite()) {
+        (false, false) => Ordering::Equal,
+        (false, true) => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (true, true) => a.partial_cmp(&b).unwrap(),
+    }
 }